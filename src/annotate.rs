@@ -0,0 +1,242 @@
+use crate::input;
+use crate::report::{SectionFinding, SourceRef};
+use anyhow::{Result, anyhow};
+use calamine::{Data, Reader, open_workbook_auto};
+use rust_xlsxwriter::{Color, Format, Note, Workbook, Worksheet};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Name of the annotated copy of the input file within the given directory,
+/// e.g. "tickoff.xlsx" becomes "<dir>/tickoff_annotated.xlsx". Names already
+/// taken by inputs with the same stem are numbered, e.g.
+/// "<dir>/tickoff_annotated_2.xlsx".
+pub fn annotated_file_name(input: &str, dir: &str, taken: &[String]) -> String {
+    let stem = Path::new(input)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("input");
+    let name = |suffix: String| {
+        Path::new(dir)
+            .join(format!("{stem}_annotated{suffix}.xlsx"))
+            .to_string_lossy()
+            .to_string()
+    };
+    (1..)
+        .map(|n| {
+            name(if n == 1 {
+                String::new()
+            } else {
+                format!("_{n}")
+            })
+        })
+        .find(|name| !taken.contains(name))
+        .unwrap()
+}
+
+/// Notes per cell of one sheet, a finding without column marks the first
+/// cell of the row. Identical findings on one cell give one note.
+fn notes_for_sheet(
+    sheet: &str,
+    findings: &[SectionFinding],
+) -> HashMap<(u32, u16), Vec<String>> {
+    let mut notes: HashMap<(u32, u16), Vec<String>> = HashMap::new();
+    for f in findings.iter() {
+        let Some(SourceRef {
+            sheet: s,
+            row,
+            column,
+        }) = &f.finding.source
+        else {
+            continue;
+        };
+        if s != sheet || *row == 0 {
+            continue;
+        }
+        let cell = (row - 1, column.unwrap_or(0) as u16);
+        let text = format!("{:?}: {}", f.finding.severity, f.finding.message);
+        let texts = notes.entry(cell).or_default();
+        if !texts.contains(&text) {
+            texts.push(text);
+        }
+    }
+    notes
+}
+
+fn write_cell(
+    sheet: &mut Worksheet,
+    row: u32,
+    col: u16,
+    value: &Data,
+    format: &Format,
+) -> Result<()> {
+    match value {
+        Data::Int(i) => {
+            sheet.write_number_with_format(row, col, *i as f64, format)?
+        }
+        Data::Float(f) => {
+            sheet.write_number_with_format(row, col, *f, format)?
+        }
+        Data::Bool(b) => {
+            sheet.write_boolean_with_format(row, col, *b, format)?
+        }
+        Data::DateTime(d) => {
+            let date = format.clone().set_num_format("yyyy-mm-dd");
+            sheet.write_number_with_format(row, col, d.as_f64(), &date)?
+        }
+        Data::Empty => sheet.write_blank(row, col, format)?,
+        v => sheet.write_string_with_format(row, col, v.to_string(), format)?,
+    };
+    Ok(())
+}
+
+/// Write a copy of the workbook with the cells of the findings highlighted
+/// and the finding texts as notes.
+/// Only the cell values are copied, not the formatting of the input.
+/// Returns the amount of annotated cells.
+pub fn write_annotated(
+    input: &str,
+    findings: &[SectionFinding],
+    output: &str,
+) -> Result<usize> {
+    if input::InputFormat::from_file(input)? == input::InputFormat::Csv {
+        return Err(anyhow!("Cannot annotate CSV file {input}"));
+    }
+    let mut workbook_in = open_workbook_auto(input).map_err(|e| {
+        anyhow!(format!("Error {e} while loading file {input}"))
+    })?;
+    let highlight = Format::new().set_background_color(Color::RGB(0xF8D0D0));
+    let plain = Format::new();
+    let mut workbook = Workbook::new();
+    let mut annotated = 0;
+    for name in workbook_in.sheet_names() {
+        let range = workbook_in.worksheet_range(&name)?;
+        let mut notes = notes_for_sheet(&name, findings);
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(&name)?;
+        let (start_row, start_col) = range.start().unwrap_or((0, 0));
+        for (y, row) in range.rows().enumerate() {
+            for (x, value) in row.iter().enumerate() {
+                let cell =
+                    (start_row + y as u32, (start_col as usize + x) as u16);
+                let format = if notes.contains_key(&cell) {
+                    &highlight
+                } else {
+                    &plain
+                };
+                write_cell(sheet, cell.0, cell.1, value, format)?;
+            }
+        }
+        // Findings outside of the used range are added as well
+        for ((row, col), texts) in notes.drain() {
+            if range.get_value((row, col as u32)).is_none() {
+                sheet.write_blank(row, col, &highlight)?;
+            }
+            let note = Note::new(texts.join("\n"))
+                .set_author("somato")
+                .set_width(400);
+            sheet.insert_note(row, col, &note)?;
+            annotated += 1;
+        }
+    }
+    workbook.save(output).map_err(|e| {
+        anyhow!(format!("Error {e} while writing annotated file {output}"))
+    })?;
+    Ok(annotated)
+}
+
+#[cfg(test)]
+mod annotate_tests {
+
+    use super::*;
+    use crate::report::{Finding, Severity};
+    use calamine::Xlsx;
+    use calamine::open_workbook;
+
+    fn gen_finding(
+        sheet: &str,
+        row: u32,
+        column: Option<u32>,
+    ) -> SectionFinding {
+        SectionFinding {
+            section: None,
+            finding: Finding {
+                severity: Severity::Error,
+                message: format!("Problem in {sheet}"),
+                source: Some(SourceRef::new(sheet, row, column)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_annotated_file_name() {
+        assert_eq!(
+            annotated_file_name("a/b/tickoff.xlsx", "out", &[]),
+            Path::new("out")
+                .join("tickoff_annotated.xlsx")
+                .to_string_lossy()
+        );
+        let taken = [
+            annotated_file_name("a/tickoff.xlsx", "out", &[]),
+            annotated_file_name("b/tickoff.xlsx", "out", &[]),
+        ];
+        assert_eq!(
+            annotated_file_name("c/tickoff.ods", "out", &taken),
+            Path::new("out")
+                .join("tickoff_annotated_2.xlsx")
+                .to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn test_notes_for_sheet() {
+        let findings = [
+            gen_finding("PER", 9, Some(1)),
+            gen_finding("PER", 9, Some(1)),
+            gen_finding("PER", 10, None),
+            gen_finding("GER", 9, Some(1)),
+        ];
+        let notes = notes_for_sheet("PER", &findings);
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[&(8, 1)], vec!["Error: Problem in PER"]);
+        assert_eq!(notes[&(9, 0)], vec!["Error: Problem in PER"]);
+    }
+
+    #[test]
+    fn test_write_annotated() {
+        let file = std::env::temp_dir().join("somato_test_annotated.xlsx");
+        let file = file.to_str().unwrap();
+        let findings =
+            [gen_finding("PER", 9, Some(0)), gen_finding("GER", 8, None)];
+        let annotated = write_annotated(
+            "tests/test_data/tickoff_synthetic.xlsx",
+            &findings,
+            file,
+        )
+        .unwrap();
+        assert_eq!(annotated, 2);
+        let mut original: Xlsx<_> =
+            open_workbook("tests/test_data/tickoff_synthetic.xlsx").unwrap();
+        let mut copy: Xlsx<_> = open_workbook(file).unwrap();
+        assert_eq!(original.sheet_names(), copy.sheet_names());
+        let range_original = original.worksheet_range("PER").unwrap();
+        let range_copy = copy.worksheet_range("PER").unwrap();
+        assert_eq!(range_original.get_size(), range_copy.get_size());
+        assert_eq!(
+            range_original.get_value((8, 0)),
+            range_copy.get_value((8, 0))
+        );
+    }
+
+    #[test]
+    fn test_write_annotated_csv() {
+        let file = std::env::temp_dir().join("somato_test_annotated_csv.xlsx");
+        assert!(
+            write_annotated(
+                "tests/test_data/members_synthetic.csv",
+                &[],
+                file.to_str().unwrap()
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use crate::location::Location;
+use crate::member::{self, Member, Shares};
+use crate::report::Reporter;
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+/// Physical limits of a depot. Limits that are not set are not checked.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Capacity {
+    pub location: Location,
+    /// Maximum of collectors per distribution
+    pub collectors: Option<usize>,
+    /// Maximum of shares of all types, i.e. crates, per distribution
+    pub shares: Option<u32>,
+}
+
+/// Capacity configured for the location
+pub fn capacity_for<'a>(
+    capacities: &'a [Capacity],
+    location: &Location,
+) -> Option<&'a Capacity> {
+    capacities.iter().find(|c| c.location == *location)
+}
+
+/// Sum of the shares of all types
+fn total(shares: &Shares) -> u32 {
+    shares.iter().map(|(_, a)| a).sum()
+}
+
+/// Warn when the collectors or shares of a depot exceed its capacity.
+/// Returns the amount of warnings.
+pub fn check_capacity(
+    capacity: &Capacity,
+    collectors: usize,
+    shares: &Shares,
+    reporter: &mut dyn Reporter,
+) -> usize {
+    let mut warnings = 0;
+    if let Some(max) = capacity.collectors
+        && collectors > max
+    {
+        reporter.warning(format!(
+            "{collectors} collectors exceed the capacity of {max} at {:?}",
+            capacity.location
+        ));
+        warnings += 1;
+    }
+    if let Some(max) = capacity.shares
+        && total(shares) > max
+    {
+        reporter.warning(format!(
+            "{} shares ({shares}) exceed the capacity of {max} at {:?}",
+            total(shares),
+            capacity.location
+        ));
+        warnings += 1;
+    }
+    warnings
+}
+
+/// Warn for every move planned after the date that would overload the
+/// target depot. The load is taken at the date of the move from all members
+/// under contract then, without jokers.
+/// Returns the amount of warnings.
+pub fn check_planned_moves(
+    members: &[Member],
+    capacities: &[Capacity],
+    date: &NaiveDate,
+    reporter: &mut dyn Reporter,
+) -> usize {
+    let mut warnings = 0;
+    for m in members.iter() {
+        for planned in m.moves.iter().filter(|p| p.date > *date) {
+            let Some(capacity) = capacity_for(capacities, &planned.to) else {
+                continue;
+            };
+            let collectors: Vec<Member> = members
+                .iter()
+                .filter(|o| {
+                    o.is_active_at(&planned.date)
+                        && o.location_at(&planned.date) == planned.to
+                })
+                .cloned()
+                .collect();
+            let shares = member::count_shares(&collectors);
+            let over_collectors = capacity
+                .collectors
+                .is_some_and(|max| collectors.len() > max);
+            let over_shares =
+                capacity.shares.is_some_and(|max| total(&shares) > max);
+            if over_collectors || over_shares {
+                reporter.warning_at(
+                    format!(
+                        "Planned move of {} {} {} to {:?} on {} overloads the \
+                         depot: {} collectors, {} shares",
+                        m.contract_no,
+                        m.surname,
+                        m.forename,
+                        planned.to,
+                        planned.date,
+                        collectors.len(),
+                        total(&shares)
+                    ),
+                    Some(m.source(Some(member::COLUMN_CHANGES))),
+                );
+                warnings += 1;
+            }
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod capacity_tests {
+
+    use super::*;
+    use crate::member::{BIG, DepotMove, SMALL};
+    use crate::report::{MemoryReporter, Severity};
+    use crate::test_common::test_common::*;
+
+    fn gen_capacity(
+        location: Location,
+        collectors: Option<usize>,
+        shares: Option<u32>,
+    ) -> Capacity {
+        Capacity {
+            location,
+            collectors,
+            shares,
+        }
+    }
+
+    #[test]
+    fn test_check_capacity() {
+        let shares = Shares::from([(BIG, 5), (SMALL, 5)]);
+        let mut reporter = MemoryReporter::default();
+        let capacity = gen_capacity(Location::Perouse, Some(3), Some(10));
+        assert_eq!(check_capacity(&capacity, 3, &shares, &mut reporter), 0);
+        let capacity = gen_capacity(Location::Perouse, Some(2), Some(9));
+        assert_eq!(check_capacity(&capacity, 3, &shares, &mut reporter), 2);
+        let capacity = gen_capacity(Location::Perouse, None, None);
+        assert_eq!(check_capacity(&capacity, 3, &shares, &mut reporter), 0);
+        assert_eq!(reporter.count(Severity::Warning), 2);
+    }
+
+    #[test]
+    fn test_check_planned_moves() {
+        let date = NaiveDate::from_ymd_opt(2025, 11, 7).unwrap();
+        let [mut a, mut b, c] = gen_members();
+        b.location = Location::Gerlingen;
+        a.moves.push(DepotMove {
+            date: NaiveDate::from_ymd_opt(2025, 11, 14).unwrap(),
+            to: Location::Gerlingen,
+        });
+        let members = vec![a.clone(), b, c];
+        let capacities = [gen_capacity(Location::Gerlingen, Some(2), Some(3))];
+        let mut reporter = MemoryReporter::default();
+        // Two collectors with four shares in Gerlingen after the move
+        assert_eq!(
+            check_planned_moves(&members, &capacities, &date, &mut reporter),
+            1
+        );
+        assert_eq!(reporter.count(Severity::Warning), 1);
+        let capacities = [gen_capacity(Location::Gerlingen, Some(2), None)];
+        assert_eq!(
+            check_planned_moves(&members, &capacities, &date, &mut reporter),
+            0
+        );
+        // Moves that already happened are not planned any more
+        let later = NaiveDate::from_ymd_opt(2025, 11, 14).unwrap();
+        let capacities = [gen_capacity(Location::Gerlingen, Some(1), None)];
+        assert_eq!(
+            check_planned_moves(&members, &capacities, &later, &mut reporter),
+            0
+        );
+        assert_eq!(a.location_at(&later), Location::Gerlingen);
+    }
+}
@@ -0,0 +1,372 @@
+use crate::member;
+use crate::report::Severity;
+use crate::somato::{Analysis, LocationSummary};
+use anyhow::{Result, anyhow};
+use std::fmt::Write;
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; margin-bottom: 1em; }
+th, td { border: 1px solid #bbb; padding: 0.2em 0.6em; text-align: left; }
+th { background: #eee; }
+td.number { text-align: right; }
+tr.total td { font-weight: bold; }
+td.mismatch { background: #f8d0d0; }
+tr.error td { background: #f8d0d0; }
+tr.warning td { background: #fcebc8; }
+tr.info td { background: #dbe8fb; }
+summary { cursor: pointer; margin: 0.3em 0; }
+";
+
+/// Escape text for use in HTML
+pub fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+fn number_cell(value: u32, mismatch: bool) -> String {
+    if mismatch {
+        format!("<td class=\"number mismatch\">{value}</td>")
+    } else {
+        format!("<td class=\"number\">{value}</td>")
+    }
+}
+
+fn write_summary(html: &mut String, analysis: &Analysis) -> Result<()> {
+    let types = analysis.total_shares.types();
+    // One cell per share type, marked when it differs from the expected
+    let cells = |shares: &member::Shares, expected: Option<&member::Shares>| {
+        types
+            .iter()
+            .map(|t| {
+                let mismatch =
+                    expected.is_some_and(|e| e.get(t) != shares.get(t));
+                number_cell(shares.get(t), mismatch)
+            })
+            .collect::<String>()
+    };
+    let titles = |prefix: &str| {
+        types
+            .iter()
+            .map(|t| format!("<th>{prefix}{}</th>", escape(t)))
+            .collect::<String>()
+    };
+    writeln!(html, "<h2>Summary per location</h2>")?;
+    writeln!(
+        html,
+        "<table>\n<tr><th>Location</th><th>Members</th>{}<th>Jokers</th>\
+         <th>Substitutions</th>{}{}</tr>",
+        types
+            .iter()
+            .map(|t| format!("<th>{}</th>", escape(&member::share_title(t))))
+            .collect::<String>(),
+        titles("Collected "),
+        titles("Tickoff "),
+    )?;
+    let mut members = 0;
+    let mut jokers = 0;
+    let mut substitutions = 0;
+    let mut collected = member::Shares::default();
+    let mut expected = member::Shares::default();
+    let mut tick_off = member::Shares::default();
+    for l in analysis.locations.iter() {
+        writeln!(
+            html,
+            "<tr><td>{:?}</td>{}{}{}{}{}{}</tr>",
+            l.location,
+            number_cell(l.members.len() as u32, false),
+            cells(&l.shares, None),
+            number_cell(l.jokers.len() as u32, false),
+            number_cell(l.substitutions.len() as u32, false),
+            cells(&l.collected, None),
+            cells(&l.tick_off, Some(&l.expected)),
+        )?;
+        members += l.members.len() as u32;
+        jokers += l.jokers.len() as u32;
+        substitutions += l.substitutions.len() as u32;
+        collected += &l.collected;
+        expected += &l.expected;
+        tick_off += &l.tick_off;
+    }
+    writeln!(
+        html,
+        "<tr class=\"total\"><td>Total</td>{}{}{}{}{}{}</tr>\n</table>",
+        number_cell(members, false),
+        cells(&analysis.total_shares, None),
+        number_cell(jokers, false),
+        number_cell(substitutions, false),
+        cells(&collected, None),
+        cells(&tick_off, Some(&expected)),
+    )?;
+    Ok(())
+}
+
+/// Overview of the redistributed jokered shares, only written when shares
+/// were redistributed
+fn write_redistribution(html: &mut String, analysis: &Analysis) -> Result<()> {
+    let locations: Vec<&LocationSummary> = analysis
+        .locations
+        .iter()
+        .filter(|l| {
+            !l.redistribution.given.is_empty()
+                || !l.redistribution.received.is_empty()
+        })
+        .collect();
+    if locations.is_empty() {
+        return Ok(());
+    }
+    let types = analysis.total_shares.types();
+    let cells = |shares: &member::Shares| {
+        types
+            .iter()
+            .map(|t| number_cell(shares.get(t), false))
+            .collect::<String>()
+    };
+    let titles = |prefix: &str| {
+        types
+            .iter()
+            .map(|t| format!("<th>{prefix}{}</th>", escape(t)))
+            .collect::<String>()
+    };
+    writeln!(html, "<h2>Redistribution per location</h2>")?;
+    writeln!(
+        html,
+        "<table>\n<tr><th>Location</th>{}{}{}{}</tr>",
+        titles("Jokered "),
+        titles("Given "),
+        titles("Received "),
+        titles("Unused "),
+    )?;
+    for l in locations {
+        let r = &l.redistribution;
+        writeln!(
+            html,
+            "<tr><td>{:?}</td>{}{}{}{}</tr>",
+            l.location,
+            cells(&r.jokered),
+            cells(&r.given),
+            cells(&r.received),
+            cells(&r.unused()),
+        )?;
+    }
+    writeln!(html, "</table>")?;
+    Ok(())
+}
+
+fn write_findings(html: &mut String, analysis: &Analysis) -> Result<()> {
+    writeln!(html, "<h2>Findings</h2>")?;
+    if analysis.findings.is_empty() {
+        writeln!(html, "<p>No findings.</p>")?;
+        return Ok(());
+    }
+    writeln!(
+        html,
+        "<table>\n<tr><th>Severity</th><th>Section</th><th>Message</th>\
+         <th>Source</th></tr>"
+    )?;
+    for f in analysis.findings.iter() {
+        let class = match f.finding.severity {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        let source = f
+            .finding
+            .source
+            .as_ref()
+            .map(|s| s.to_string())
+            .unwrap_or_default();
+        writeln!(
+            html,
+            "<tr class=\"{class}\"><td>{:?}</td><td>{}</td><td>{}</td>\
+             <td>{}</td></tr>",
+            f.finding.severity,
+            escape(f.section.as_deref().unwrap_or_default()),
+            escape(&f.finding.message),
+            escape(&source),
+        )?;
+    }
+    writeln!(html, "</table>")?;
+    Ok(())
+}
+
+fn write_members(
+    html: &mut String,
+    location: &LocationSummary,
+    types: &[&str],
+) -> Result<()> {
+    writeln!(
+        html,
+        "<details>\n<summary>{:?} ({} members)</summary>",
+        location.location,
+        location.members.len()
+    )?;
+    writeln!(
+        html,
+        "<table>\n<tr><th>Contract</th><th>Member</th><th>Surname</th>\
+         <th>Forename</th>{}<th>Joker</th></tr>",
+        types
+            .iter()
+            .map(|t| format!("<th>{}</th>", escape(&member::share_title(t))))
+            .collect::<String>()
+    )?;
+    for m in location.members.iter() {
+        let joker = if member::has_joker(m, &location.jokers) {
+            "Joker"
+        } else {
+            ""
+        };
+        writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td>{}\
+             <td>{joker}</td></tr>",
+            escape(&m.contract_no),
+            m.member_no,
+            escape(&m.surname),
+            escape(&m.forename),
+            types
+                .iter()
+                .map(|t| number_cell(m.shares.get(t), false))
+                .collect::<String>(),
+        )?;
+    }
+    writeln!(html, "</table>\n</details>")?;
+    Ok(())
+}
+
+/// Render the analysis as a self-contained HTML page
+pub fn render_html(analysis: &Analysis) -> Result<String> {
+    let mut html = String::new();
+    writeln!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Somato report {}</title>\n<style>{STYLE}</style>\n\
+         </head>\n<body>",
+        analysis.date
+    )?;
+    writeln!(html, "<h1>Somato report for {}</h1>", analysis.date)?;
+    writeln!(html, "<p>Accumulated {} warnings</p>", analysis.warnings)?;
+    write_summary(&mut html, analysis)?;
+    write_redistribution(&mut html, analysis)?;
+    write_findings(&mut html, analysis)?;
+    writeln!(html, "<h2>Members</h2>")?;
+    for location in analysis.locations.iter() {
+        write_members(&mut html, location, &analysis.total_shares.types())?;
+    }
+    writeln!(html, "</body>\n</html>")?;
+    Ok(html)
+}
+
+/// Write the analysis as HTML file
+pub fn write_html(analysis: &Analysis, file: &str) -> Result<()> {
+    std::fs::write(file, render_html(analysis)?).map_err(|e| {
+        anyhow!(format!("Error {e} while writing report file {file}"))
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod html_tests {
+
+    use super::*;
+    use crate::location::Location;
+    use crate::member::{BIG, SMALL};
+    use crate::report::{Finding, SectionFinding, SourceRef};
+    use crate::test_common::test_common::*;
+    use chrono::NaiveDate;
+
+    fn gen_analysis() -> Analysis {
+        let members = gen_members().to_vec();
+        Analysis {
+            date: NaiveDate::from_ymd_opt(2025, 11, 7).unwrap(),
+            locations: vec![LocationSummary {
+                location: Location::Perouse,
+                members: members.clone(),
+                shares: member::count_shares(&members),
+                jokers: vec![gen_joker_a()],
+                collected: member::Shares::from([(BIG, 5), (SMALL, 5)]),
+                expected: member::Shares::from([(BIG, 5), (SMALL, 5)]),
+                tick_off: member::Shares::from([(BIG, 5), (SMALL, 4)]),
+                substitutions: Vec::new(),
+                redistribution: Default::default(),
+            }],
+            total_shares: member::count_shares(&members),
+            warnings: 1,
+            findings: vec![SectionFinding {
+                section: Some("Analysis for: Perouse".to_string()),
+                finding: Finding {
+                    severity: Severity::Error,
+                    message: "Cannot find <Duck>".to_string(),
+                    source: Some(SourceRef::new("Ernteverträge", 3, Some(2))),
+                },
+            }],
+        }
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a <b> & \"c\""), "a &lt;b&gt; &amp; &quot;c&quot;");
+    }
+
+    #[test]
+    fn test_render_html() {
+        let html = render_html(&gen_analysis()).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h1>Somato report for 2025-11-07</h1>"));
+        assert!(html.contains("<tr class=\"error\"><td>Error</td>"));
+        assert!(html.contains("Cannot find &lt;Duck&gt;"));
+        assert!(html.contains("<td>Ernteverträge!C3</td>"));
+        assert!(html.contains("<td class=\"number mismatch\">4</td>"));
+        assert!(html.contains("<summary>Perouse (3 members)</summary>"));
+        assert!(html.trim_end().ends_with("</html>"));
+        assert!(!html.contains("Redistribution"));
+    }
+
+    #[test]
+    fn test_render_html_received_not_mismatch() {
+        let mut analysis = gen_analysis();
+        let location = &mut analysis.locations[0];
+        location.redistribution.received =
+            member::Shares::from([(BIG, 1), (SMALL, 0)]);
+        location.expected = member::Shares::from([(BIG, 6), (SMALL, 4)]);
+        location.tick_off = member::Shares::from([(BIG, 6), (SMALL, 4)]);
+        let html = render_html(&analysis).unwrap();
+        assert!(!html.contains("number mismatch"));
+    }
+
+    #[test]
+    fn test_render_html_redistribution() {
+        let mut analysis = gen_analysis();
+        analysis.locations[0].redistribution =
+            crate::redistribution::RedistributionSummary {
+                jokered: member::Shares::from([(BIG, 2), (SMALL, 0)]),
+                given: member::Shares::from([(BIG, 1), (SMALL, 0)]),
+                received: member::Shares::default(),
+            };
+        let html = render_html(&analysis).unwrap();
+        assert!(html.contains("<h2>Redistribution per location</h2>"));
+        assert!(html.contains(
+            "<tr><td>Perouse</td><td class=\"number\">2</td>\
+             <td class=\"number\">0</td><td class=\"number\">1</td>"
+        ));
+    }
+
+    #[test]
+    fn test_write_html() {
+        let file = std::env::temp_dir().join("somato_test_report.html");
+        let file = file.to_str().unwrap();
+        write_html(&gen_analysis(), file).unwrap();
+        let content = std::fs::read_to_string(file).unwrap();
+        assert_eq!(content, render_html(&gen_analysis()).unwrap());
+    }
+}
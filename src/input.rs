@@ -0,0 +1,188 @@
+use anyhow::{Result, anyhow};
+use calamine::{
+    CellErrorType, Data, Ods, Range, Reader, Xls, Xlsx, open_workbook,
+};
+use serde::Deserialize;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Supported formats for input files
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum InputFormat {
+    Xlsx,
+    Xls,
+    Ods,
+    Csv,
+}
+
+impl InputFormat {
+    /// Guess the format from the file extension
+    pub fn from_file(file: &str) -> Result<InputFormat> {
+        let extension = Path::new(file)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        let format = match extension.as_str() {
+            "xlsx" | "xlsm" => InputFormat::Xlsx,
+            "xls" => InputFormat::Xls,
+            "ods" => InputFormat::Ods,
+            "csv" => InputFormat::Csv,
+            _ => {
+                return Err(anyhow!(
+                    "Cannot detect input format of file {file}"
+                ));
+            }
+        };
+        Ok(format)
+    }
+}
+
+/// Read one sheet of the given file.
+/// The format is taken from the file extension unless it is given explicitly.
+/// A CSV file only contains one sheet, if the file name contains "{sheet}"
+/// it is replaced by the sheet name. This way one CSV file per sheet can be
+/// used, e.g. "tickoff_{sheet}.csv".
+/// Returns None when the sheet does not exist.
+pub fn read_sheet(
+    file: &str,
+    sheet: &str,
+    format: Option<InputFormat>,
+) -> Result<Option<Range<Data>>> {
+    let format = match format {
+        Some(f) => f,
+        None => InputFormat::from_file(file)?,
+    };
+    match format {
+        InputFormat::Xlsx => read_workbook::<Xlsx<_>>(file, sheet),
+        InputFormat::Xls => read_workbook::<Xls<_>>(file, sheet),
+        InputFormat::Ods => read_workbook::<Ods<_>>(file, sheet),
+        InputFormat::Csv => {
+            let sheet_file = file.replace("{sheet}", sheet);
+            if sheet_file != file && !Path::new(&sheet_file).exists() {
+                return Ok(None);
+            }
+            read_csv(&sheet_file)
+        }
+    }
+}
+
+fn read_workbook<R>(file: &str, sheet: &str) -> Result<Option<Range<Data>>>
+where
+    R: Reader<BufReader<File>>,
+    R::Error: std::fmt::Display,
+{
+    let mut workbook: R = open_workbook(file)
+        .map_err(|e| anyhow!(format!("Error {e} while loading file {file}")))?;
+    Ok(workbook.worksheet_range(sheet).ok())
+}
+
+/// Read a CSV file into a range of cells.
+/// Dates in ISO format and "#N/A" errors are converted, all other values are
+/// kept as strings.
+fn read_csv(file: &str) -> Result<Option<Range<Data>>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_path(file)
+        .map_err(|e| anyhow!(format!("Error {e} while loading file {file}")))?;
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        rows.push(record.iter().map(csv_cell).collect::<Vec<Data>>());
+    }
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    if rows.is_empty() || width == 0 {
+        return Ok(Some(Range::empty()));
+    }
+    let mut range =
+        Range::new((0, 0), (rows.len() as u32 - 1, width as u32 - 1));
+    for (y, row) in rows.into_iter().enumerate() {
+        for (x, cell) in row.into_iter().enumerate() {
+            range.set_value((y as u32, x as u32), cell);
+        }
+    }
+    Ok(Some(range))
+}
+
+fn csv_cell(value: &str) -> Data {
+    if value.is_empty() {
+        Data::Empty
+    } else if value == "#N/A" {
+        Data::Error(CellErrorType::NA)
+    } else if chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok() {
+        Data::DateTimeIso(value.to_string())
+    } else {
+        Data::String(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod input_tests {
+
+    use super::*;
+    use calamine::DataType;
+
+    #[test]
+    fn test_from_file() {
+        assert_eq!(
+            InputFormat::from_file("a.xlsx").unwrap(),
+            InputFormat::Xlsx
+        );
+        assert_eq!(InputFormat::from_file("a.XLS").unwrap(), InputFormat::Xls);
+        assert_eq!(
+            InputFormat::from_file("a/b.ods").unwrap(),
+            InputFormat::Ods
+        );
+        assert_eq!(InputFormat::from_file("a.csv").unwrap(), InputFormat::Csv);
+        assert!(InputFormat::from_file("a.txt").is_err());
+        assert!(InputFormat::from_file("a").is_err());
+    }
+
+    #[test]
+    fn test_csv_cell() {
+        assert_eq!(csv_cell(""), Data::Empty);
+        assert_eq!(csv_cell("Duck"), Data::String("Duck".to_string()));
+        assert_eq!(csv_cell("12").as_i64(), Some(12));
+        assert_eq!(csv_cell("#N/A"), Data::Error(CellErrorType::NA));
+        assert_eq!(
+            csv_cell("2025-11-07").as_date(),
+            chrono::NaiveDate::from_ymd_opt(2025, 11, 7)
+        );
+    }
+
+    #[test]
+    fn test_read_sheet_csv() {
+        let range = read_sheet(
+            "tests/test_data/tickoff_synthetic_{sheet}.csv",
+            "GER",
+            None,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(range.get_size(), (66, 8));
+        assert_eq!(range.get_value((7, 0)).unwrap(), &"Duck, D.");
+    }
+
+    #[test]
+    fn test_read_sheet_missing() {
+        let range = read_sheet(
+            "tests/test_data/tickoff_synthetic.xlsx",
+            "Missing",
+            None,
+        )
+        .unwrap();
+        assert!(range.is_none());
+        let range = read_sheet(
+            "tests/test_data/tickoff_synthetic_{sheet}.csv",
+            "Missing",
+            None,
+        )
+        .unwrap();
+        assert!(range.is_none());
+        let range = read_sheet("tests/test_data/missing.csv", "Missing", None);
+        assert!(range.is_err());
+    }
+}
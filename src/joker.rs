@@ -1,20 +1,24 @@
+use crate::input;
 use crate::location::Location;
-use crate::member::Member;
+use crate::member::{Member, MemberStatus, ShareType, Shares};
+use crate::report::{Reporter, SourceRef};
 use anyhow::{Result, anyhow};
-use calamine::{Data, DataType, Reader, Xlsx, open_workbook};
+use calamine::{Data, DataType};
 use chrono::NaiveDate;
-use colored::Colorize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Clone)]
+/// Name of the sheet with the joker list
+pub const JOKER_SHEET: &str = "Eingabe";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Joker {
     pub date: NaiveDate,
     pub surname: String,
     pub forename: String,
     pub warning: u32,
     pub location: Location,
-    pub big: u32,
-    pub small: u32,
+    pub shares: Shares,
     pub line: u32,
 }
 
@@ -28,8 +32,7 @@ impl Joker {
         forename: &Data,
         warning: &Data,
         location: &Data,
-        big: &Data,
-        small: &Data,
+        shares: &[(&str, &Data)],
         line: u32,
     ) -> Result<Joker> {
         // println!("{date:?}");
@@ -37,6 +40,12 @@ impl Joker {
             Data::DateTime(date) => {
                 NaiveDate::from(date.as_datetime().unwrap())
             }
+            // Dates from ods and CSV files
+            Data::DateTimeIso(_) => date.as_date().ok_or(anyhow!(
+                "Cannot parse date \"{}\" on line {}",
+                date,
+                line
+            ))?,
             _ => {
                 return Err(anyhow!(
                     "Cannot parse date \"{}\" on line {}",
@@ -61,7 +70,6 @@ impl Joker {
                 // println!("Forename: Found Error N/A")
             }
             s => {
-                println!("Found inacceptable data in forename {s:?}");
                 return Err(anyhow!(
                     "Found inacceptable data type in forename: {}",
                     s
@@ -80,16 +88,18 @@ impl Joker {
             .trim()
             .to_string();
 
+        let mut amounts = Shares::default();
+        for (name, value) in shares {
+            amounts.set(name, value.as_i64().unwrap_or(88) as u32);
+        }
+
         let joker = Self {
             date: ndate,
             surname,
             forename,
             warning: warning.as_i64().expect("Cannot parse warning") as u32,
             location,
-            // big: big.as_i64().expect("Cannot parse big") as u32,
-            // small: small.as_i64().expect("Cannot parse small") as u32,
-            big: big.as_i64().unwrap_or(88) as u32,
-            small: small.as_i64().unwrap_or(88) as u32,
+            shares: amounts,
             line,
         };
         // println!("{}", joker);
@@ -97,47 +107,55 @@ impl Joker {
     }
 }
 
+impl Joker {
+    /// Cell of this joker in the joker sheet
+    pub fn source(&self, column: Option<u32>) -> SourceRef {
+        SourceRef::new(JOKER_SHEET, self.line, column)
+    }
+}
+
 impl fmt::Display for Joker {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Joker: {} {} {} {} {:?} {} {}",
+            "Joker: {} {} {} {} {:?} {}",
             self.date,
             self.surname,
             self.forename,
             self.warning,
             self.location,
-            self.small,
-            self.big
+            self.shares.amounts()
         )
     }
 }
 
-pub fn check_joker_names(members: &[Member], jokers: &[Joker]) -> Result<u32> {
+pub fn check_joker_names(
+    members: &[Member],
+    jokers: &[Joker],
+    reporter: &mut dyn Reporter,
+) -> Result<u32> {
     let mut joker_warnings = 0;
     let warn_limit = 10;
     'outer: for j in jokers.iter() {
         for m in members.iter() {
-            if j.surname.to_lowercase() == m.surname.to_lowercase()
-                && j.forename.to_lowercase() == m.forename.to_lowercase()
-            {
+            if m.is_holder(&j.surname, &j.forename) {
                 // println!("Found {}", j.surname);
                 continue 'outer;
             }
-            if j.surname.to_lowercase() == m.surname.to_lowercase() && !m.active
+            if j.surname.to_lowercase() == m.surname.to_lowercase()
+                && m.status == MemberStatus::Inactive
             {
                 // println!("  Ignoring inactive entry {}", j.surname);
                 continue 'outer;
             }
         }
         if joker_warnings < warn_limit {
-            println!(
-                "{}",
+            reporter.warning_at(
                 format!(
-                    "    Cannot find Joker line {} in member list name: \"{}\" forename: \"{}\"",
+                    "Cannot find Joker line {} in member list name: \"{}\" forename: \"{}\"",
                     j.line, j.surname, j.forename
-                )
-                .bright_red()
+                ),
+                Some(j.source(Some(1))),
             );
         }
 
@@ -146,41 +164,47 @@ pub fn check_joker_names(members: &[Member], jokers: &[Joker]) -> Result<u32> {
     Ok(joker_warnings)
 }
 
-pub fn check_joker_sizes(members: &[Member], jokers: &[Joker]) -> Result<u32> {
+pub fn check_joker_sizes(
+    members: &[Member],
+    jokers: &[Joker],
+    share_types: &[ShareType],
+    reporter: &mut dyn Reporter,
+) -> Result<u32> {
     let mut joker_warnings = 0;
     // let warn_limit = 5;
     // not absolutely elegant as we found them earlier and now we are searching
     // them again.
     'outer: for j in jokers.iter() {
         for m in members.iter() {
-            if !m.active {
+            if !m.status.counts_for_distribution() {
                 continue;
             }
-            if j.surname.to_lowercase() == m.surname.to_lowercase()
-                && j.forename.to_lowercase() == m.forename.to_lowercase()
-            {
-                // println!("Found {}", j.surname);
-                if j.big != m.big {
-                    println!(
-                        "{}",
+            if m.is_holder(&j.surname, &j.forename) {
+                if !m.status.may_book_jokers() {
+                    reporter.warning_at(
                         format!(
-                            "    Joker size for big {} does not match with member list: {} {} line {}",
-                            m.surname, j.big, m.big, j.line
-                        )
-                        .bright_red()
+                            "Joker of {} {} with status {} line {}",
+                            m.surname, m.forename, m.status, j.line
+                        ),
+                        Some(j.source(Some(1))),
                     );
                     joker_warnings += 1;
                 }
-                if j.small != m.small {
-                    println!(
-                        "{}",
-                        format!(
-                            "    Joker size for small {} does not match with member list: {} {} line {}",
-                            m.surname, j.small, m.small, j.line
-                        )
-                        .bright_red()
-                    );
-                    joker_warnings += 1;
+                // println!("Found {}", j.surname);
+                for t in share_types.iter() {
+                    let (joker, member) =
+                        (j.shares.get(&t.name), m.shares.get(&t.name));
+                    if joker != member {
+                        reporter.warning_at(
+                            format!(
+                                "Joker size for {} {} does not match with \
+                                 member list: {} {} line {}",
+                                t.name, m.surname, joker, member, j.line
+                            ),
+                            Some(j.source(Some(t.joker_column))),
+                        );
+                        joker_warnings += 1;
+                    }
                 }
                 continue 'outer;
             }
@@ -189,32 +213,35 @@ pub fn check_joker_sizes(members: &[Member], jokers: &[Joker]) -> Result<u32> {
     Ok(joker_warnings)
 }
 
-pub fn check_joker_list(members: &[Member], jokers: &[Joker]) -> Result<u32> {
-    println!("Checking Joker List");
+pub fn check_joker_list(
+    members: &[Member],
+    jokers: &[Joker],
+    share_types: &[ShareType],
+    reporter: &mut dyn Reporter,
+) -> Result<u32> {
+    reporter.debug("Checking Joker List");
     let mut joker_warnings = 0;
     // let warn_limit = 5;
-    joker_warnings += check_joker_names(members, jokers)?;
-    joker_warnings += check_joker_sizes(members, jokers)?;
-    println!(
-        "{}",
-        format!("  Overall Joker warnings {}", joker_warnings).bright_red()
-    );
+    joker_warnings += check_joker_names(members, jokers, reporter)?;
+    joker_warnings +=
+        check_joker_sizes(members, jokers, share_types, reporter)?;
+    reporter.message(&format!("  Overall Joker warnings {}", joker_warnings));
     Ok(joker_warnings)
 }
 
-pub fn read_jokers(joker_file: &str) -> Result<Vec<Joker>> {
-    let mut excel: Xlsx<_> = open_workbook(joker_file).map_err(|e| {
-        anyhow!(format!("Error {e} while loading joker file {joker_file}"))
-    })?;
-
+pub fn read_jokers(
+    joker_file: &str,
+    format: Option<input::InputFormat>,
+    share_types: &[ShareType],
+) -> Result<Vec<Joker>> {
     let mut jokers = Vec::new();
-    if let Ok(r) = excel.worksheet_range("Eingabe") {
-        let mut line = 2;
-        for row in r.rows().skip(1) {
+    if let Some(r) = input::read_sheet(joker_file, JOKER_SHEET, format)? {
+        for (line, row) in (2..).zip(r.rows().skip(1)) {
             // println!("row={:?}, row[0]={:?}", row, row[0]);
             // println!(
             //     "{} {} {} {} {} {} {} {}",
-            //     row[0], row[1], row[2], row[3], row[4], row[5], row[6], row[7]
+            //     row[0], row[1], row[2], row[3],
+            //     row[4], row[5], row[6], row[7]
             // );
             // if let Data::DateTime(date) = row[0] {
             //     println!("{}", NaiveDate::from(date.as_datetime().unwrap()));
@@ -224,13 +251,17 @@ pub fn read_jokers(joker_file: &str) -> Result<Vec<Joker>> {
             let forename = &row[2];
             let warning = &row[3];
             let location = &row[4];
-            let big = &row[5];
-            let small = &row[6];
+            let shares: Vec<(&str, &Data)> = share_types
+                .iter()
+                .map(|t| {
+                    let value = row.get(t.joker_column as usize);
+                    (t.name.as_str(), value.unwrap_or(&Data::Empty))
+                })
+                .collect();
             let joker = Joker::new(
-                date, name, forename, warning, location, big, small, line,
+                date, name, forename, warning, location, &shares, line,
             )?;
             jokers.push(joker);
-            line += 1;
         }
     }
     Ok(jokers)
@@ -240,33 +271,30 @@ pub fn filter_jokers_by_date(
     jokers: &[Joker],
     date: &chrono::NaiveDate,
 ) -> JokerList {
-    let result: JokerList = jokers
+    jokers
         .iter()
         .filter(|&j| j.date == *date)
         .cloned()
-        .collect();
-    println!("  Filtered {} jokers at {}", result.len(), date);
-    result
+        .collect()
 }
 
 pub fn filter_jokers_by_location(
     jokers: JokerList,
     location: &Location,
 ) -> JokerList {
-    let result: JokerList = jokers
+    jokers
         .into_iter()
         .filter(|j| j.location == *location)
-        .collect();
-    println!("  Filtered {} jokers at {:?}", result.len(), location);
-    result
+        .collect()
 }
 
 #[cfg(test)]
 mod joker_tests {
 
     use super::*;
+    use crate::member::{BIG, SMALL, share_types};
+    use crate::report::{MemoryReporter, QuietReporter, Severity};
     use crate::test_common::test_common;
-    use injectorpp::interface::injector::*;
 
     #[test]
     fn test_new_wrong_date() {
@@ -276,8 +304,7 @@ mod joker_tests {
             &Data::String("John".to_string()),
             &Data::Int(88),
             &Data::String(" Perouse ".to_string()),
-            &Data::Int(80),
-            &Data::Int(81),
+            &[(BIG, &Data::Int(80)), (SMALL, &Data::Int(81))],
             88,
         );
         assert!(j.is_err());
@@ -301,8 +328,7 @@ mod joker_tests {
             &Data::String("John".to_string()),
             &Data::Int(88),
             &Data::String("Perouse".to_string()),
-            &Data::Int(80),
-            &Data::Int(81),
+            &[(BIG, &Data::Int(80)), (SMALL, &Data::Int(81))],
             88,
         );
         println!("{:?}", j);
@@ -314,8 +340,8 @@ mod joker_tests {
         assert_eq!(j.forename, "John");
         assert_eq!(j.warning, 88);
         assert_eq!(j.location, Location::Perouse);
-        assert_eq!(j.big, 80);
-        assert_eq!(j.small, 81);
+        assert_eq!(j.shares.get(BIG), 80);
+        assert_eq!(j.shares.get(SMALL), 81);
         assert_eq!(j.line, 88);
     }
     #[test]
@@ -330,8 +356,7 @@ mod joker_tests {
             &Data::String("  John ".to_string()),
             &Data::Int(88),
             &Data::String("  Perouse ".to_string()),
-            &Data::Int(80),
-            &Data::Int(81),
+            &[(BIG, &Data::Int(80)), (SMALL, &Data::Int(81))],
             88,
         );
         println!("{:?}", j);
@@ -343,8 +368,8 @@ mod joker_tests {
         assert_eq!(j.forename, "John");
         assert_eq!(j.warning, 88);
         assert_eq!(j.location, Location::Perouse);
-        assert_eq!(j.big, 80);
-        assert_eq!(j.small, 81);
+        assert_eq!(j.shares.get(BIG), 80);
+        assert_eq!(j.shares.get(SMALL), 81);
         assert_eq!(j.line, 88);
     }
 
@@ -353,7 +378,12 @@ mod joker_tests {
         let members = test_common::gen_members();
         let jokers = vec![test_common::gen_joker_a()];
 
-        let result = check_joker_list(&members, &jokers);
+        let result = check_joker_list(
+            &members,
+            &jokers,
+            &share_types(None),
+            &mut QuietReporter::default(),
+        );
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), 0);
     }
@@ -361,30 +391,26 @@ mod joker_tests {
     #[test]
     fn test_check_joker_list_fail() {
         let members = test_common::gen_members();
-        let jokers = vec![test_common::gen_joker_a()];
-        let mut injector = InjectorPP::new();
-        injector
-            .when_called(
-                injectorpp::func!(fn (check_joker_names)( &[Member], &[Joker]) -> Result<u32>),
-            )
-            .will_execute(injectorpp::fake!(
-                func_type: fn(_m:&[Member], _j:&[Joker]) -> Result<u32>,
-                returns: Ok(9),
-                times: 1
-            ));
-        injector
-            .when_called(
-                injectorpp::func!(fn (check_joker_sizes)( &[Member], &[Joker]) -> Result<u32>),
-            )
-            .will_execute(injectorpp::fake!(
-                func_type: fn(_m:&[Member], _j:&[Joker]) -> Result<u32>,
-                returns: Ok(7),
-                times: 1
-            ));
+        let mut jokers = vec![
+            test_common::gen_joker_a(),
+            test_common::gen_joker_b(),
+            test_common::gen_joker_c(),
+        ];
+        // One name and two size warnings
+        jokers[0].surname = "Nobody".to_string();
+        jokers[1].shares.set(BIG, 7);
+        jokers[2].shares.set(SMALL, 7);
 
-        let result = check_joker_list(&members, &jokers);
+        let mut reporter = MemoryReporter::default();
+        let result = check_joker_list(
+            &members,
+            &jokers,
+            &share_types(None),
+            &mut reporter,
+        );
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), 16);
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(reporter.count(Severity::Warning), 3);
     }
 
     #[test]
@@ -397,12 +423,12 @@ mod joker_tests {
             forename: "Nono".to_string(),
             warning: 0,
             location: Location::Perouse,
-            big: 0,
-            small: 2,
+            shares: Shares::from([(BIG, 0), (SMALL, 2)]),
             line: 88,
         };
         jokers.push(j);
-        let result = check_joker_names(&members, &jokers);
+        let result =
+            check_joker_names(&members, &jokers, &mut QuietReporter::default());
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), 1);
     }
@@ -411,7 +437,12 @@ mod joker_tests {
     fn test_check_joker_sizes() {
         let members = test_common::gen_members();
         let jokers = vec![test_common::gen_joker_a()];
-        let result = check_joker_sizes(&members, &jokers);
+        let result = check_joker_sizes(
+            &members,
+            &jokers,
+            &share_types(None),
+            &mut QuietReporter::default(),
+        );
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), 0);
     }
@@ -424,7 +455,12 @@ mod joker_tests {
             test_common::gen_joker_b(),
             test_common::gen_joker_c(),
         ];
-        let result = check_joker_sizes(&members, &jokers);
+        let result = check_joker_sizes(
+            &members,
+            &jokers,
+            &share_types(None),
+            &mut QuietReporter::default(),
+        );
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), 0);
     }
@@ -436,13 +472,18 @@ mod joker_tests {
             test_common::gen_joker_b(),
             test_common::gen_joker_c(),
         ];
-        jokers[0].big = 7;
-        jokers[0].small = 7;
-        jokers[1].big = 7;
-        jokers[1].small = 7;
-        jokers[2].big = 7;
-        jokers[2].small = 7;
-        let result = check_joker_sizes(&members, &jokers);
+        jokers[0].shares.set(BIG, 7);
+        jokers[0].shares.set(SMALL, 7);
+        jokers[1].shares.set(BIG, 7);
+        jokers[1].shares.set(SMALL, 7);
+        jokers[2].shares.set(BIG, 7);
+        jokers[2].shares.set(SMALL, 7);
+        let result = check_joker_sizes(
+            &members,
+            &jokers,
+            &share_types(None),
+            &mut QuietReporter::default(),
+        );
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), 6);
     }
@@ -454,11 +495,33 @@ mod joker_tests {
             test_common::gen_joker_b(),
             test_common::gen_joker_c(),
         ];
-        jokers[0].big = 7;
-        jokers[0].small = 7;
-        members[0].active = false;
-        let result = check_joker_sizes(&members, &jokers);
+        jokers[0].shares.set(BIG, 7);
+        jokers[0].shares.set(SMALL, 7);
+        members[0].status = MemberStatus::Inactive;
+        let result = check_joker_sizes(
+            &members,
+            &jokers,
+            &share_types(None),
+            &mut QuietReporter::default(),
+        );
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), 0);
     }
+    #[test]
+    fn test_check_joker_sizes_trial() {
+        let mut members = test_common::gen_members();
+        members[0].status = MemberStatus::Trial;
+        let mut reporter = MemoryReporter::default();
+        let result = check_joker_sizes(
+            &members,
+            &[test_common::gen_joker_a()],
+            &share_types(None),
+            &mut reporter,
+        );
+        assert_eq!(result.unwrap(), 1);
+        assert_eq!(
+            reporter.findings[0].finding.message,
+            "Joker of Testerin Alice with status trial line 88"
+        );
+    }
 }
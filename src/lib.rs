@@ -1,6 +1,20 @@
+pub mod annotate;
+pub mod capacity;
+pub mod html;
+pub mod input;
 pub mod joker;
 pub mod location;
 pub mod member;
+pub mod packing;
+pub mod rebalance;
+pub mod redistribution;
+pub mod report;
+pub mod roster;
+pub mod snapshot;
 pub mod somato;
+pub mod source;
+pub mod statistics;
+pub mod substitution;
+pub mod summary;
 pub mod test_common;
 pub mod tickoff;
@@ -1,7 +1,8 @@
 use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
-#[derive(EnumIter, Debug, Clone, PartialEq)]
+#[derive(EnumIter, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Location {
     Perouse,
     Gerlingen,
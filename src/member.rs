@@ -1,21 +1,212 @@
+use crate::input;
 use crate::joker;
 use crate::location::Location;
+use crate::report::{Reporter, SourceRef};
 use anyhow::{Result, anyhow};
-use calamine::{Data, DataType, Reader, Xlsx, open_workbook};
-use colored::Colorize;
+use calamine::{Data, DataType};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
 use std::collections;
 use std::fmt;
 
-#[derive(Debug, Clone)]
+/// Name of the sheet with the member list
+pub const MEMBER_SHEET: &str = "Ernteverträge";
+/// Sheet with the co-holders of shared contracts
+pub const CO_HOLDER_SHEET: &str = "Teilernte";
+/// Columns of the member sheet, starting at 0
+pub const COLUMN_MEMBER_NO: u32 = 1;
+pub const COLUMN_SURNAME: u32 = 2;
+pub const COLUMN_LOCATION: u32 = 15;
+pub const COLUMN_START: u32 = 16;
+pub const COLUMN_END: u32 = 18;
+pub const COLUMN_STATUS: u32 = 19;
+pub const COLUMN_CHANGES: u32 = 20;
+
+/// State of a contract, parsed from the status column
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum MemberStatus {
+    #[default]
+    Active,
+    Inactive,
+    Paused,
+    Cancelled,
+    Trial,
+}
+
+impl MemberStatus {
+    /// Shares of the member are packed and distributed
+    pub fn counts_for_distribution(self) -> bool {
+        matches!(self, Self::Active | Self::Cancelled | Self::Trial)
+    }
+
+    /// Member may set jokers
+    pub fn may_book_jokers(self) -> bool {
+        matches!(self, Self::Active | Self::Cancelled)
+    }
+
+    /// Member is expected on the tick-off list
+    pub fn on_tick_off_list(self) -> bool {
+        matches!(self, Self::Active | Self::Cancelled | Self::Trial)
+    }
+}
+
+impl fmt::Display for MemberStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Self::Active => "active",
+            Self::Inactive => "inactive",
+            Self::Paused => "paused",
+            Self::Cancelled => "cancelled",
+            Self::Trial => "trial",
+        };
+        write!(f, "{text}")
+    }
+}
+
+/// Maps the texts of the status column to the status
+pub type StatusMap = collections::HashMap<String, MemberStatus>;
+
+/// The texts used in the member list, extended or overridden by the
+/// given mapping
+pub fn status_map(extra: Option<&StatusMap>) -> StatusMap {
+    let mut map: StatusMap = [
+        ("aktiv", MemberStatus::Active),
+        ("inaktiv", MemberStatus::Inactive),
+        ("pausiert", MemberStatus::Paused),
+        ("gekündigt", MemberStatus::Cancelled),
+        ("Probe", MemberStatus::Trial),
+    ]
+    .into_iter()
+    .map(|(text, status)| (text.to_string(), status))
+    .collect();
+    if let Some(extra) = extra {
+        map.extend(extra.iter().map(|(t, s)| (t.clone(), *s)));
+    }
+    map
+}
+
+/// Names of the default share types
+pub const BIG: &str = "big";
+pub const SMALL: &str = "small";
+
+/// A kind of share and the columns of its amount in the input files
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ShareType {
+    pub name: String,
+    /// Column in the member sheet, starting at 0
+    pub member_column: u32,
+    /// Column in the joker sheet, starting at 0
+    pub joker_column: u32,
+    /// Column of the names in the tickoff sheets, the amounts follow in the
+    /// next column. Starting at 0.
+    pub tickoff_column: u32,
+}
+
+/// The configured share types or big and small if none are configured
+pub fn share_types(configured: Option<&Vec<ShareType>>) -> Vec<ShareType> {
+    match configured {
+        Some(types) => types.clone(),
+        None => vec![
+            ShareType {
+                name: BIG.to_string(),
+                member_column: 5,
+                joker_column: 5,
+                tickoff_column: 0,
+            },
+            ShareType {
+                name: SMALL.to_string(),
+                member_column: 6,
+                joker_column: 6,
+                tickoff_column: 5,
+            },
+        ],
+    }
+}
+
+/// Period in which a member does not collect, both days included
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Pause {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+/// Planned change of the depot from the date on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepotMove {
+    pub date: NaiveDate,
+    pub to: Location,
+}
+
+/// Weeks in which a co-holder collects a shared contract
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Schedule {
+    /// The households split every delivery
+    #[default]
+    Always,
+    /// Even calendar weeks, the contract holder collects the odd ones
+    Even,
+    /// Odd calendar weeks, the contract holder collects the even ones
+    Odd,
+}
+
+impl Schedule {
+    /// Parse the schedule column: "immer" or empty, "gerade", "ungerade"
+    pub fn parse(text: &str) -> Result<Schedule> {
+        match text.trim().to_lowercase().as_str() {
+            "" | "immer" | "always" => Ok(Schedule::Always),
+            "gerade" | "even" => Ok(Schedule::Even),
+            "ungerade" | "odd" => Ok(Schedule::Odd),
+            other => Err(anyhow!(format!("Unknown schedule {other}"))),
+        }
+    }
+
+    /// Check if the schedule has its turn in the ISO week of the date
+    pub fn has_turn(self, date: &NaiveDate) -> bool {
+        let week = date.iso_week().week();
+        match self {
+            Schedule::Always => true,
+            Schedule::Even => week.is_multiple_of(2),
+            Schedule::Odd => !week.is_multiple_of(2),
+        }
+    }
+}
+
+/// Second household of a shared contract (Teilernte)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoHolder {
+    pub surname: String,
+    pub forename: String,
+    #[serde(default)]
+    pub schedule: Schedule,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Member {
     pub contract_no: String,
     pub member_no: u32,
     pub surname: String,
     pub forename: String,
-    pub big: u32,
-    pub small: u32,
+    pub shares: Shares,
     pub location: Location,
-    pub active: bool,
+    #[serde(default)]
+    pub status: MemberStatus,
+    /// First delivery of the contract
+    #[serde(default)]
+    pub start: Option<NaiveDate>,
+    /// Last delivery of the contract
+    #[serde(default)]
+    pub end: Option<NaiveDate>,
+    #[serde(default)]
+    pub pauses: Vec<Pause>,
+    #[serde(default)]
+    pub co_holders: Vec<CoHolder>,
+    /// Planned depot moves, sorted by date
+    #[serde(default)]
+    pub moves: Vec<DepotMove>,
     pub line: u32,
 }
 
@@ -28,10 +219,13 @@ impl Member {
         member_no: &Data,
         surname: &Data,
         forename: &Data,
-        big: &Data,
-        small: &Data,
+        shares: &[(&str, &Data)],
         location: &Data,
-        active: &Data,
+        status: &Data,
+        statuses: &StatusMap,
+        start: &Data,
+        end: &Data,
+        changes: &Data,
         line: u32,
     ) -> Result<Self> {
         // Its text not a number
@@ -43,27 +237,24 @@ impl Member {
                     member_no, e
                 ))
             })?;
-        let location_str = location.as_string().unwrap().trim().to_string();
-        let active_bool = match active.as_string().unwrap().as_str() {
-            "aktiv" => true,
-            "inaktiv" => false,
-            _ => {
-                return Err(anyhow!(format!(
-                    "Error while parsing activity {}",
-                    active
-                )));
-            }
-        };
-        let big = big
-            .as_string()
-            .ok_or(anyhow!("Cannot parse value for big amount in line {line}"))?
-            .parse::<u32>()?;
-        let small = small
+        let location_str =
+            location.as_string().unwrap_or_default().trim().to_string();
+        let status = *status
             .as_string()
-            .ok_or(anyhow!(
-                "Cannot parse value for small amount in line {line}"
-            ))?
-            .parse::<u32>()?;
+            .and_then(|s| statuses.get(s.trim()))
+            .ok_or(anyhow!(format!(
+            "Error while parsing activity {status}"
+        )))?;
+        let mut amounts = Shares::default();
+        for (name, value) in shares {
+            let amount = value
+                .as_string()
+                .ok_or(anyhow!(
+                    "Cannot parse value for {name} amount in line {line}"
+                ))?
+                .parse::<u32>()?;
+            amounts.set(name, amount);
+        }
         let surname = surname
             .as_string()
             .ok_or(anyhow!("Cannot parse surname {surname}"))?
@@ -79,11 +270,17 @@ impl Member {
             member_no,
             surname,
             forename,
-            big,
-            small,
-            location: Location::parse(&location_str).unwrap(),
-            active: active_bool,
-            line: 88,
+            shares: amounts,
+            location: Location::parse(&location_str).map_err(|e| {
+                anyhow!(format!("Error {e} while reading member line {line}"))
+            })?,
+            status,
+            start: parse_date_cell(start, line)?,
+            end: parse_date_cell(end, line)?,
+            pauses: parse_pauses(&changes.to_string(), line)?,
+            co_holders: Vec::new(),
+            moves: parse_moves(&changes.to_string(), line)?,
+            line,
         };
         // println!("{}", member);
         Ok(member)
@@ -99,48 +296,178 @@ impl Member {
         big: u32,
         small: u32,
         location: Location,
-        active: bool,
+        status: MemberStatus,
     ) -> Member {
         Member {
             contract_no: contract_no.to_string(),
             surname: surname.to_string(),
             forename: forename.to_string(),
             member_no,
-            big,
-            small,
+            shares: Shares::from([(BIG, big), (SMALL, small)]),
             location,
-            active,
+            status,
+            start: None,
+            end: None,
+            pauses: Vec::new(),
+            co_holders: Vec::new(),
+            moves: Vec::new(),
             line: 88,
         }
     }
+
+    /// Check if the member is under contract and not paused at the date
+    pub fn is_active_at(&self, date: &NaiveDate) -> bool {
+        self.status.counts_for_distribution()
+            && self.start.is_none_or(|s| s <= *date)
+            && self.end.is_none_or(|e| *date <= e)
+            && !self.pauses.iter().any(|p| p.from <= *date && *date <= p.to)
+    }
+
+    /// Depot of the member at the date, taking planned moves into account
+    pub fn location_at(&self, date: &NaiveDate) -> Location {
+        self.moves
+            .iter()
+            .rfind(|m| m.date <= *date)
+            .map(|m| m.to.clone())
+            .unwrap_or(self.location.clone())
+    }
+
+    /// Names of the contract holder followed by the co-holders
+    pub fn holders(&self) -> Vec<(&str, &str)> {
+        let mut holders = vec![(self.surname.as_str(), self.forename.as_str())];
+        holders.extend(
+            self.co_holders
+                .iter()
+                .map(|c| (c.surname.as_str(), c.forename.as_str())),
+        );
+        holders
+    }
+
+    /// Check if the name belongs to the contract holder or a co-holder
+    pub fn is_holder(&self, surname: &str, forename: &str) -> bool {
+        self.holders().iter().any(|(s, f)| {
+            s.to_lowercase() == surname.to_lowercase()
+                && f.to_lowercase() == forename.to_lowercase()
+        })
+    }
+
+    /// Name of the household collecting at the date. A co-holder with an
+    /// alternating schedule takes the weeks of its turn, the contract holder
+    /// all others.
+    pub fn collector_at(&self, date: &NaiveDate) -> (&str, &str) {
+        self.co_holders
+            .iter()
+            .find(|c| {
+                c.schedule != Schedule::Always && c.schedule.has_turn(date)
+            })
+            .map(|c| (c.surname.as_str(), c.forename.as_str()))
+            .unwrap_or((&self.surname, &self.forename))
+    }
+}
+
+/// Date of a contract cell, empty cells and "-" mean no date
+pub(crate) fn parse_date_cell(
+    value: &Data,
+    line: u32,
+) -> Result<Option<NaiveDate>> {
+    let error = || anyhow!("Cannot parse date \"{value}\" on line {line}");
+    match value {
+        Data::Empty => Ok(None),
+        Data::String(s) if s.trim().is_empty() || s.trim() == "-" => Ok(None),
+        Data::String(s) => Ok(Some(
+            NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+                .map_err(|_| error())?,
+        )),
+        Data::DateTime(d) => {
+            Ok(Some(NaiveDate::from(d.as_datetime().ok_or_else(error)?)))
+        }
+        // Dates from ods and CSV files
+        Data::DateTimeIso(_) => Ok(Some(value.as_date().ok_or_else(error)?)),
+        _ => Err(error()),
+    }
+}
+
+/// Pauses noted in the changes column, e.g.
+/// "Pause 2025-07-01 bis 2025-08-15; Pause 2025-10-03 - 2025-10-10"
+fn parse_pauses(changes: &str, line: u32) -> Result<Vec<Pause>> {
+    let re = lazy_regex::regex!(
+        r"Pause (\d{4}-\d{2}-\d{2}) *(?:-|bis) *(\d{4}-\d{2}-\d{2})"
+    );
+    let mut pauses = Vec::new();
+    for c in re.captures_iter(changes) {
+        let date = |i: usize| {
+            NaiveDate::parse_from_str(&c[i], "%Y-%m-%d").map_err(|e| {
+                anyhow!(format!("Error {e} while parsing pause on line {line}"))
+            })
+        };
+        let pause = Pause {
+            from: date(1)?,
+            to: date(2)?,
+        };
+        if pause.to < pause.from {
+            return Err(anyhow!(format!(
+                "Pause ends before it starts on line {line}"
+            )));
+        }
+        pauses.push(pause);
+    }
+    Ok(pauses)
+}
+
+/// Depot moves noted in the changes column, e.g.
+/// "Wechsel nach Leonberg ab 2026-01-02"
+fn parse_moves(changes: &str, line: u32) -> Result<Vec<DepotMove>> {
+    let re = lazy_regex::regex!(
+        r"Wechsel nach ([[:alpha:] ]+?) ab (\d{4}-\d{2}-\d{2})"
+    );
+    let mut moves = Vec::new();
+    for c in re.captures_iter(changes) {
+        let date =
+            NaiveDate::parse_from_str(&c[2], "%Y-%m-%d").map_err(|e| {
+                anyhow!(format!("Error {e} while parsing move on line {line}"))
+            })?;
+        let to = Location::parse(&c[1]).map_err(|e| {
+            anyhow!(format!("Error {e} while parsing move on line {line}"))
+        })?;
+        moves.push(DepotMove { date, to });
+    }
+    moves.sort_by_key(|m| m.date);
+    Ok(moves)
+}
+
+impl Member {
+    /// Cell of this member in the member sheet
+    pub fn source(&self, column: Option<u32>) -> SourceRef {
+        SourceRef::new(MEMBER_SHEET, self.line, column)
+    }
 }
 
 impl fmt::Display for Member {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Member: {} {} {} {} {} {}",
+            "Member: {} {} {} {} {}",
             self.contract_no,
             self.member_no,
             self.surname,
             self.forename,
-            self.big,
-            self.small
+            self.shares.amounts()
         )
     }
 }
 
-pub fn read_members(members_file: &str) -> Result<Vec<Member>> {
-    let table_name = "Ernteverträge";
-    let mut excel: Xlsx<_> = open_workbook(members_file).map_err(|e| {
-        anyhow!(format!(
-            "Error {e} whle loading members file {members_file}"
-        ))
-    })?;
-    let mut line = 2;
+/// Read the member list. Missing cells count as empty; a member whose
+/// location, status, amounts or dates cannot be parsed fails the whole read
+/// with the line of the member.
+pub fn read_members(
+    members_file: &str,
+    format: Option<input::InputFormat>,
+    statuses: &StatusMap,
+    share_types: &[ShareType],
+) -> Result<Vec<Member>> {
     let mut members = Vec::new();
-    if let Ok(r) = excel.worksheet_range(table_name) {
-        for row in r.rows().skip(1).take(251) {
+    if let Some(r) = input::read_sheet(members_file, MEMBER_SHEET, format)? {
+        for (line, row) in (2..).zip(r.rows().skip(1).take(251)) {
             // println!("row={:?}, row[0]={:?}", row, row[0]);
             // println!(
             //     "{} {} {} {} {} {} {} {}",
@@ -155,46 +482,100 @@ pub fn read_members(members_file: &str) -> Result<Vec<Member>> {
             let member_no = &row[1];
             let surname = &row[2];
             let forename = &row[3];
-            let big = &row[5];
-            let small = &row[6];
-            let location = &row[15];
-            let active = &row[19];
+            let shares: Vec<(&str, &Data)> = share_types
+                .iter()
+                .map(|t| {
+                    let value = row.get(t.member_column as usize);
+                    (t.name.as_str(), value.unwrap_or(&Data::Empty))
+                })
+                .collect();
+            let cell =
+                |column: u32| row.get(column as usize).unwrap_or(&Data::Empty);
+            let location = cell(COLUMN_LOCATION);
+            let status = cell(COLUMN_STATUS);
+            let start = cell(COLUMN_START);
+            let end = cell(COLUMN_END);
+            let changes = cell(COLUMN_CHANGES);
             let member = Member::new(
                 contract_no,
                 member_no,
                 surname,
                 forename,
-                big,
-                small,
+                &shares,
                 location,
-                active,
+                status,
+                statuses,
+                start,
+                end,
+                changes,
                 line,
             )?;
             members.push(member);
-            line += 1;
         }
     };
-    println!("Parsed members: {}", members.len());
     if members.is_empty() {
         return Err(anyhow!("Found no members"));
     }
     Ok(members)
 }
 
-pub fn check_member_list(members: &[Member]) -> u32 {
+/// Read the co-holders of shared contracts and add them to their members.
+/// Columns: contract no, surname, forename, schedule.
+pub fn read_co_holders(
+    co_holder_file: &str,
+    format: Option<input::InputFormat>,
+    members: &mut [Member],
+) -> Result<()> {
+    let Some(r) = input::read_sheet(co_holder_file, CO_HOLDER_SHEET, format)?
+    else {
+        return Ok(());
+    };
+    for (line, row) in (2..).zip(r.rows().skip(1)) {
+        if (0..3).any(|x| row.get(x).is_none_or(|c| c.is_empty())) {
+            continue;
+        }
+        let text = |x: usize| {
+            row.get(x)
+                .and_then(|c| c.as_string())
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+        let contract_no = text(0);
+        let schedule = Schedule::parse(&text(3)).map_err(|e| {
+            anyhow!(format!("Error {e} while reading co-holder line {line}"))
+        })?;
+        let member = members
+            .iter_mut()
+            .find(|m| m.contract_no == contract_no)
+            .ok_or(anyhow!(format!(
+                "Unknown contract {contract_no} in co-holder line {line}"
+            )))?;
+        member.co_holders.push(CoHolder {
+            surname: text(1),
+            forename: text(2),
+            schedule,
+        });
+    }
+    Ok(())
+}
+
+pub fn check_member_list(
+    members: &[Member],
+    reporter: &mut dyn Reporter,
+) -> u32 {
     let mut warnings = 0;
-    println!("Checking member list");
+    reporter.debug("Checking member list");
     let mut surname_set = collections::HashSet::new();
     for member in members.iter() {
         if surname_set.insert(&member.surname) {
         } else {
-            println!(
-                "{}",
+            reporter.warning_at(
                 format!(
-                    "  Duplicated surname: {} {} {}",
+                    "Duplicated surname: {} {} {}",
                     member.surname, member.contract_no, member.member_no
-                )
-                .bright_red()
+                ),
+                Some(member.source(Some(COLUMN_SURNAME))),
             );
             warnings += 1;
         }
@@ -203,16 +584,15 @@ pub fn check_member_list(members: &[Member]) -> u32 {
     for member in members.iter() {
         if member_no_set.insert(&member.member_no) {
         } else {
-            println!(
-                "{}",
+            reporter.warning_at(
                 format!(
-                    "  Duplicated member number: {} {} {} {}",
+                    "Duplicated member number: {} {} {} {}",
                     member.surname,
                     member.forename,
                     member.contract_no,
                     member.member_no
-                )
-                .bright_red()
+                ),
+                Some(member.source(Some(COLUMN_MEMBER_NO))),
             );
             warnings += 1;
         }
@@ -221,13 +601,12 @@ pub fn check_member_list(members: &[Member]) -> u32 {
     for member in members.iter() {
         if contract_no_set.insert(&member.contract_no) {
         } else {
-            println!(
-                "{}",
+            reporter.warning_at(
                 format!(
-                    "  Duplicated contract number: {} {} {}",
+                    "Duplicated contract number: {} {} {}",
                     member.surname, member.contract_no, member.member_no
-                )
-                .bright_red()
+                ),
+                Some(member.source(Some(0))),
             );
             warnings += 1;
         }
@@ -235,76 +614,326 @@ pub fn check_member_list(members: &[Member]) -> u32 {
     warnings
 }
 
-pub fn filter_active_members(members: MemberList) -> MemberList {
-    let result: MemberList = members.into_iter().filter(|m| m.active).collect();
-    println!("Found {} active members", result.len());
-    result
+/// Return the members that are under contract and not paused at the date
+pub fn filter_active_members(
+    members: MemberList,
+    date: &NaiveDate,
+) -> MemberList {
+    members
+        .into_iter()
+        .filter(|m| m.is_active_at(date))
+        .collect()
 }
 
+/// Check if the member has set one of the given jokers.
+pub fn has_joker(member: &Member, jokers: &[joker::Joker]) -> bool {
+    jokers
+        .iter()
+        .any(|j| member.is_holder(&j.surname, &j.forename))
+}
+
+/// Return the members that have not set one of the given jokers.
 pub fn filter_jokers(
     members: &[Member],
     jokers: &[joker::Joker],
 ) -> MemberList {
-    let mut result: MemberList = Vec::new();
-
-    'outer: for m in members.iter() {
-        for j in jokers {
-            if j.surname.to_lowercase() == m.surname.to_lowercase()
-                && j.forename.to_lowercase() == m.forename.to_lowercase()
-            {
-                println!(
-                    "  Joker set: {} {} from {:?}",
-                    j.surname.to_string().bright_blue(),
-                    j.forename,
-                    m.location
-                );
-                continue 'outer;
-            }
-        }
-        result.push(m.clone());
-    }
-
-    println!("  Found {} active members with no jokers", result.len());
-    result
+    members
+        .iter()
+        .filter(|m| !has_joker(m, jokers))
+        .cloned()
+        .collect()
 }
 
 pub fn filter_members_by_location(
     members: &MemberList,
     location: &Location,
 ) -> MemberList {
-    let result: MemberList = members
-        .clone()
-        .into_iter()
+    members
+        .iter()
         .filter(|m| m.location == *location)
-        .collect();
-    println!("  Found {} members in {:?}", result.len(), location);
-    // println!("{:?}", result);
-    result
+        .cloned()
+        .collect()
 }
 
-pub fn filter_members_by_small(members: &MemberList) -> MemberList {
-    let result: MemberList = members
-        .clone()
-        .into_iter()
-        .filter(|m| m.small >= 1)
-        .collect();
-    // println!("  Found {} members with size small", result.len());
-    // println!("{:?}", result);
-    result
+/// Title of a share type for headings, e.g. "Big"
+pub fn share_title(share_type: &str) -> String {
+    let mut chars = share_type.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Return the members that hold at least one share of the given type
+pub fn filter_members_by_share(
+    members: &MemberList,
+    share_type: &str,
+) -> MemberList {
+    members
+        .iter()
+        .filter(|m| m.shares.get(share_type) >= 1)
+        .cloned()
+        .collect()
+}
+
+/// Amount per share type, e.g. big 2, small 0.
+/// A missing share type counts as amount 0.
+#[derive(Debug, Clone, Default, Eq, Serialize, Deserialize)]
+pub struct Shares(collections::BTreeMap<String, u32>);
+
+impl Shares {
+    /// Amount of the given share type
+    pub fn get(&self, share_type: &str) -> u32 {
+        self.0.get(share_type).copied().unwrap_or(0)
+    }
+
+    pub fn set(&mut self, share_type: &str, amount: u32) {
+        self.0.insert(share_type.to_string(), amount);
+    }
+
+    /// Share types and their amounts, sorted by share type
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.0.iter().map(|(t, a)| (t.as_str(), *a))
+    }
+
+    /// Share types known to these shares, sorted
+    pub fn types(&self) -> Vec<&str> {
+        self.0.keys().map(|t| t.as_str()).collect()
+    }
+
+    /// Share types whose amounts differ, with this and the other amount
+    pub fn differences(&self, other: &Shares) -> Vec<(String, u32, u32)> {
+        let mut types = self.types();
+        types.extend(other.types());
+        types.sort();
+        types.dedup();
+        types
+            .into_iter()
+            .filter(|t| self.get(t) != other.get(t))
+            .map(|t| (t.to_string(), self.get(t), other.get(t)))
+            .collect()
+    }
+
+    /// The amounts separated by spaces, e.g. "2 0"
+    pub fn amounts(&self) -> String {
+        self.0
+            .values()
+            .map(|a| a.to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// Check if there is no share at all
+    pub fn is_empty(&self) -> bool {
+        self.0.values().all(|a| *a == 0)
+    }
 }
 
-pub fn filter_members_by_big(members: &MemberList) -> MemberList {
-    let result: MemberList =
-        members.clone().into_iter().filter(|m| m.big >= 1).collect();
-    // println!("  Found {} members with size big", result.len());
-    // println!("{:?}", result);
-    result
+impl<const N: usize> From<[(&str, u32); N]> for Shares {
+    fn from(amounts: [(&str, u32); N]) -> Self {
+        Shares(
+            amounts
+                .into_iter()
+                .map(|(t, a)| (t.to_string(), a))
+                .collect(),
+        )
+    }
+}
+
+impl PartialEq for Shares {
+    fn eq(&self, other: &Self) -> bool {
+        self.differences(other).is_empty()
+    }
+}
+
+impl std::hash::Hash for Shares {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        // Consistent with eq, share types with amount 0 do not count
+        for (t, a) in self.iter().filter(|(_, a)| *a > 0) {
+            t.hash(state);
+            a.hash(state);
+        }
+    }
+}
+
+impl std::ops::AddAssign<&Shares> for Shares {
+    fn add_assign(&mut self, other: &Shares) {
+        for (t, a) in other.iter() {
+            *self.0.entry(t.to_string()).or_insert(0) += a;
+        }
+    }
+}
+
+impl fmt::Display for Shares {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "none");
+        }
+        let text: Vec<String> =
+            self.iter().map(|(t, a)| format!("{t} {a}")).collect();
+        write!(f, "{}", text.join(", "))
+    }
+}
+
+/// Sum up the shares of the given members.
+/// In contrast to filter_members_by_share this counts shares, a member can
+/// hold several shares of a type.
+pub fn count_shares(members: &[Member]) -> Shares {
+    members.iter().fold(Shares::default(), |mut shares, m| {
+        shares += &m.shares;
+        shares
+    })
+}
+
+/// Change of a member between two member lists, the member is the new state
+/// except for removed members
+#[derive(Debug, Clone)]
+pub enum MemberChange {
+    Added(Member),
+    Removed(Member),
+    Resized {
+        member: Member,
+        old: Shares,
+    },
+    Moved {
+        member: Member,
+        from: Location,
+    },
+    Activity {
+        member: Member,
+    },
+    Renamed {
+        member: Member,
+        surname: String,
+        forename: String,
+    },
+}
+
+impl fmt::Display for MemberChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |m: &Member| {
+            format!("{} {} {}", m.contract_no, m.surname, m.forename)
+        };
+        match self {
+            Self::Added(m) => write!(
+                f,
+                "New contract {} at {:?}, {}",
+                name(m),
+                m.location,
+                m.shares
+            ),
+            Self::Removed(m) => {
+                write!(f, "Removed contract {} at {:?}", name(m), m.location)
+            }
+            Self::Resized { member: m, old } => write!(
+                f,
+                "Changed shares of {} from {} to {}",
+                name(m),
+                old,
+                m.shares
+            ),
+            Self::Moved { member: m, from } => write!(
+                f,
+                "Moved {} from {:?} to {:?}",
+                name(m),
+                from,
+                m.location
+            ),
+            Self::Activity { member: m } => {
+                write!(f, "Contract {} is now {}", name(m), m.status)
+            }
+            Self::Renamed {
+                member: m,
+                surname,
+                forename,
+            } => write!(
+                f,
+                "Renamed {} {} {} to {} {}",
+                m.contract_no, surname, forename, m.surname, m.forename
+            ),
+        }
+    }
+}
+
+/// Compare two member lists, members are identified by contract and member
+/// number. A member can have several changes.
+pub fn diff_members(old: &[Member], new: &[Member]) -> Vec<MemberChange> {
+    let same = |a: &Member, b: &Member| {
+        a.contract_no == b.contract_no && a.member_no == b.member_no
+    };
+    let mut changes = Vec::new();
+    for n in new.iter() {
+        let Some(o) = old.iter().find(|o| same(o, n)) else {
+            changes.push(MemberChange::Added(n.clone()));
+            continue;
+        };
+        if o.shares != n.shares {
+            changes.push(MemberChange::Resized {
+                member: n.clone(),
+                old: o.shares.clone(),
+            });
+        }
+        if o.location != n.location {
+            changes.push(MemberChange::Moved {
+                member: n.clone(),
+                from: o.location.clone(),
+            });
+        }
+        if o.status != n.status {
+            changes.push(MemberChange::Activity { member: n.clone() });
+        }
+        if o.surname != n.surname || o.forename != n.forename {
+            changes.push(MemberChange::Renamed {
+                member: n.clone(),
+                surname: o.surname.clone(),
+                forename: o.forename.clone(),
+            });
+        }
+    }
+    for o in old.iter().filter(|o| !new.iter().any(|n| same(o, n))) {
+        changes.push(MemberChange::Removed(o.clone()));
+    }
+    changes
+}
+
+/// Report the changes of a member list
+pub fn report_member_changes(
+    changes: &[MemberChange],
+    reporter: &mut dyn Reporter,
+) {
+    reporter.message(&format!("Member changes: {}", changes.len()));
+    for change in changes.iter() {
+        reporter.message(&format!("  {change}"));
+    }
+}
+
+/// Compare two member files and report the changes.
+/// The input format, status texts and share types are those of the
+/// configuration.
+pub fn diff_member_files(
+    old: &str,
+    new: &str,
+    format: Option<input::InputFormat>,
+    statuses: &StatusMap,
+    share_types: &[ShareType],
+    reporter: &mut dyn Reporter,
+) -> Result<Vec<MemberChange>> {
+    let changes = diff_members(
+        &read_members(old, format, statuses, share_types)?,
+        &read_members(new, format, statuses, share_types)?,
+    );
+    reporter.section(&format!("Changes from {old} to {new}"));
+    report_member_changes(&changes, reporter);
+    Ok(changes)
 }
 
 #[allow(dead_code)]
-pub fn print_members(members: &MemberList) {
+pub fn print_members(members: &MemberList, reporter: &mut dyn Reporter) {
     for m in members {
-        println!("Member: {} {} {} {}", m.surname, m.forename, m.big, m.small);
+        reporter.debug(&format!(
+            "Member: {} {} {}",
+            m.surname, m.forename, m.shares
+        ));
     }
 }
 
@@ -312,6 +941,7 @@ pub fn print_members(members: &MemberList) {
 mod member_tests {
 
     use super::*;
+    use crate::report::{MemoryReporter, Severity};
     use crate::test_common::test_common::*;
     use calamine::Data;
 
@@ -331,10 +961,13 @@ mod member_tests {
             &member_no,
             &surname,
             &forename,
-            &big,
-            &small,
+            &[(BIG, &big), (SMALL, &small)],
             &location,
             &active,
+            &status_map(None),
+            &Data::Empty,
+            &Data::Empty,
+            &Data::Empty,
             line,
         );
     }
@@ -345,10 +978,13 @@ mod member_tests {
             &Data::Int(87),
             &Data::String("Smith".to_string()),
             &Data::String("John".to_string()),
-            &Data::Int(88),
-            &Data::Int(89),
+            &[(BIG, &Data::Int(88)), (SMALL, &Data::Int(89))],
             &Data::String("Perouse".to_string()),
             &Data::String("aktiv".to_string()),
+            &status_map(None),
+            &Data::Empty,
+            &Data::Empty,
+            &Data::Empty,
             77,
         )
         .unwrap();
@@ -360,10 +996,13 @@ mod member_tests {
             &Data::Int(87),
             &Data::String("Smith".to_string()),
             &Data::String("John".to_string()),
-            &Data::Int(88),
-            &Data::Int(89),
+            &[(BIG, &Data::Int(88)), (SMALL, &Data::Int(89))],
             &Data::String("Perouse".to_string()),
             &Data::String("inaktiv".to_string()),
+            &status_map(None),
+            &Data::Empty,
+            &Data::Empty,
+            &Data::Empty,
             77,
         )
         .unwrap();
@@ -375,25 +1014,198 @@ mod member_tests {
             &Data::Int(87),
             &Data::String("Smith".to_string()),
             &Data::String("John".to_string()),
-            &Data::Int(88),
-            &Data::Int(89),
+            &[(BIG, &Data::Int(88)), (SMALL, &Data::Int(89))],
             &Data::String("Perouse".to_string()),
             &Data::String("defect".to_string()),
+            &status_map(None),
+            &Data::Empty,
+            &Data::Empty,
+            &Data::Empty,
             77,
         );
         assert!(m.is_err(), "Failed to parse activity");
     }
     #[test]
+    fn new_member_status() {
+        let new = |status: &str, statuses: &StatusMap| {
+            Member::new(
+                &Data::String("EV".to_string()),
+                &Data::Int(87),
+                &Data::String("Smith".to_string()),
+                &Data::String("John".to_string()),
+                &[(BIG, &Data::Int(1)), (SMALL, &Data::Int(0))],
+                &Data::String("Perouse".to_string()),
+                &Data::String(status.to_string()),
+                statuses,
+                &Data::Empty,
+                &Data::Empty,
+                &Data::Empty,
+                77,
+            )
+        };
+        let statuses = status_map(None);
+        let status = |s| new(s, &statuses).unwrap().status;
+        assert_eq!(status("aktiv"), MemberStatus::Active);
+        assert_eq!(status("pausiert"), MemberStatus::Paused);
+        assert_eq!(status("gekündigt"), MemberStatus::Cancelled);
+        assert_eq!(status(" Probe "), MemberStatus::Trial);
+        assert!(new("Probeabo", &statuses).is_err());
+        let extra: StatusMap = [
+            ("Probeabo".to_string(), MemberStatus::Trial),
+            ("aktiv".to_string(), MemberStatus::Paused),
+        ]
+        .into();
+        let statuses = status_map(Some(&extra));
+        assert_eq!(status("inaktiv"), MemberStatus::Inactive);
+        assert_eq!(
+            new("Probeabo", &statuses).unwrap().status,
+            MemberStatus::Trial
+        );
+        assert_eq!(
+            new("aktiv", &statuses).unwrap().status,
+            MemberStatus::Paused
+        );
+    }
+    #[test]
+    fn test_member_status_rules() {
+        let rules = |s: MemberStatus| {
+            (
+                s.counts_for_distribution(),
+                s.may_book_jokers(),
+                s.on_tick_off_list(),
+            )
+        };
+        assert_eq!(rules(MemberStatus::Active), (true, true, true));
+        assert_eq!(rules(MemberStatus::Inactive), (false, false, false));
+        assert_eq!(rules(MemberStatus::Paused), (false, false, false));
+        assert_eq!(rules(MemberStatus::Cancelled), (true, true, true));
+        assert_eq!(rules(MemberStatus::Trial), (true, false, true));
+    }
+    #[test]
+    fn new_member_dates() {
+        let m = Member::new(
+            &Data::String("EV".to_string()),
+            &Data::Int(87),
+            &Data::String("Smith".to_string()),
+            &Data::String("John".to_string()),
+            &[(BIG, &Data::Int(1)), (SMALL, &Data::Int(0))],
+            &Data::String("Perouse".to_string()),
+            &Data::String("aktiv".to_string()),
+            &status_map(None),
+            &Data::DateTimeIso("2025-03-07".to_string()),
+            &Data::String("-".to_string()),
+            &Data::String(
+                "Pause 2025-07-04 bis 2025-08-15; Pause 2025-10-03 - 2025-10-03; \
+                 Wechsel nach Weil der Stadt ab 2025-11-07"
+                    .to_string(),
+            ),
+            77,
+        )
+        .unwrap();
+        let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+        assert_eq!(m.start, Some(date(3, 7)));
+        assert_eq!(m.end, None);
+        assert_eq!(
+            m.pauses,
+            vec![
+                Pause {
+                    from: date(7, 4),
+                    to: date(8, 15)
+                },
+                Pause {
+                    from: date(10, 3),
+                    to: date(10, 3)
+                }
+            ]
+        );
+        assert_eq!(
+            m.moves,
+            vec![DepotMove {
+                date: date(11, 7),
+                to: Location::WeilDerStadt
+            }]
+        );
+        assert_eq!(m.location_at(&date(10, 31)), Location::Perouse);
+        assert_eq!(m.location_at(&date(11, 7)), Location::WeilDerStadt);
+    }
+    #[test]
+    fn new_member_date_fail() {
+        let new = |start: Data, changes: &str| {
+            Member::new(
+                &Data::String("EV".to_string()),
+                &Data::Int(87),
+                &Data::String("Smith".to_string()),
+                &Data::String("John".to_string()),
+                &[(BIG, &Data::Int(1)), (SMALL, &Data::Int(0))],
+                &Data::String("Perouse".to_string()),
+                &Data::String("aktiv".to_string()),
+                &status_map(None),
+                &start,
+                &Data::Empty,
+                &Data::String(changes.to_string()),
+                77,
+            )
+        };
+        let m = new(Data::String("soon".to_string()), "");
+        assert_eq!(
+            m.unwrap_err().to_string(),
+            "Cannot parse date \"soon\" on line 77"
+        );
+        assert!(new(Data::Empty, "Pause 2025-08-01 - 2025-07-01").is_err());
+        assert!(new(Data::Empty, "Pause 2025-02-30 - 2025-03-01").is_err());
+        assert!(new(Data::Empty, "Wechsel nach Berlin ab 2025-03-01").is_err());
+    }
+    #[test]
+    fn test_is_active_at() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+        let [mut m, ..] = gen_members();
+        assert!(m.is_active_at(&date(1, 3)));
+        m.start = Some(date(3, 7));
+        m.end = Some(date(11, 28));
+        m.pauses = vec![Pause {
+            from: date(7, 4),
+            to: date(8, 15),
+        }];
+        assert!(!m.is_active_at(&date(2, 28)));
+        assert!(m.is_active_at(&date(3, 7)));
+        assert!(!m.is_active_at(&date(7, 4)));
+        assert!(!m.is_active_at(&date(8, 15)));
+        assert!(m.is_active_at(&date(8, 22)));
+        assert!(m.is_active_at(&date(11, 28)));
+        assert!(!m.is_active_at(&date(12, 5)));
+        m.start = None;
+        m.status = MemberStatus::Paused;
+        assert!(!m.is_active_at(&date(3, 7)));
+        m.status = MemberStatus::Trial;
+        assert!(m.is_active_at(&date(3, 7)));
+    }
+    #[test]
+    fn test_filter_active_members() {
+        let date = NaiveDate::from_ymd_opt(2025, 11, 7).unwrap();
+        let mut members = gen_members().to_vec();
+        members[0].end = Some(NaiveDate::from_ymd_opt(2025, 10, 31).unwrap());
+        members[1].status = MemberStatus::Inactive;
+        let active = filter_active_members(members, &date);
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].surname, "Testeress");
+    }
+    #[test]
     fn new_big_amount_fail() {
         let m = Member::new(
             &Data::String("EV".to_string()),
             &Data::Int(88),
             &Data::String("Smith".to_string()),
             &Data::String("John".to_string()),
-            &Data::String("Fail".to_string()),
-            &Data::Int(89),
+            &[
+                (BIG, &Data::String("Fail".to_string())),
+                (SMALL, &Data::Int(89)),
+            ],
             &Data::String("Perouse".to_string()),
             &Data::String("inaktiv".to_string()),
+            &status_map(None),
+            &Data::Empty,
+            &Data::Empty,
+            &Data::Empty,
             77,
         );
         assert!(m.is_err(), "Failed to parse contract number {:?}", m);
@@ -405,10 +1217,16 @@ mod member_tests {
             &Data::Int(66),
             &Data::String("Smith".to_string()),
             &Data::String("John".to_string()),
-            &Data::Int(88),
-            &Data::String("Fail".to_string()),
+            &[
+                (BIG, &Data::Int(88)),
+                (SMALL, &Data::String("Fail".to_string())),
+            ],
             &Data::String("Perouse".to_string()),
             &Data::String("inaktiv".to_string()),
+            &status_map(None),
+            &Data::Empty,
+            &Data::Empty,
+            &Data::Empty,
             77,
         );
         assert!(m.is_err(), "Failed to parse contract number {:?}", m);
@@ -420,10 +1238,13 @@ mod member_tests {
             &Data::String("Fail".to_string()),
             &Data::String("Smith".to_string()),
             &Data::String("John".to_string()),
-            &Data::Int(88),
-            &Data::Int(89),
+            &[(BIG, &Data::Int(88)), (SMALL, &Data::Int(89))],
             &Data::String("Perouse".to_string()),
             &Data::String("inaktiv".to_string()),
+            &status_map(None),
+            &Data::Empty,
+            &Data::Empty,
+            &Data::Empty,
             77,
         );
         assert!(m.is_err(), "Failed to parse contract number {:?}", m);
@@ -435,10 +1256,13 @@ mod member_tests {
             &Data::Int(66),
             &Data::String("  Smith ".to_string()),
             &Data::String(" John  ".to_string()),
-            &Data::Int(88),
-            &Data::Int(89),
+            &[(BIG, &Data::Int(88)), (SMALL, &Data::Int(89))],
             &Data::String(" Perouse ".to_string()),
             &Data::String("inaktiv".to_string()),
+            &status_map(None),
+            &Data::Empty,
+            &Data::Empty,
+            &Data::Empty,
             77,
         );
         assert!(m.is_ok());
@@ -448,13 +1272,170 @@ mod member_tests {
         assert_eq!(m.location, Location::Perouse);
     }
     #[test]
+    fn test_schedule() {
+        // 2025-12-19 is in week 51, 2025-12-26 in week 52
+        let odd = NaiveDate::from_ymd_opt(2025, 12, 19).unwrap();
+        let even = NaiveDate::from_ymd_opt(2025, 12, 26).unwrap();
+        assert_eq!(Schedule::parse("").unwrap(), Schedule::Always);
+        assert_eq!(Schedule::parse(" Gerade ").unwrap(), Schedule::Even);
+        assert_eq!(Schedule::parse("ungerade").unwrap(), Schedule::Odd);
+        assert!(Schedule::parse("monatlich").is_err());
+        assert!(Schedule::Always.has_turn(&odd));
+        assert!(Schedule::Odd.has_turn(&odd));
+        assert!(!Schedule::Odd.has_turn(&even));
+        assert!(Schedule::Even.has_turn(&even));
+    }
+    #[test]
+    fn test_co_holders() {
+        let odd = NaiveDate::from_ymd_opt(2025, 12, 19).unwrap();
+        let even = NaiveDate::from_ymd_opt(2025, 12, 26).unwrap();
+        let [mut m, ..] = gen_members();
+        assert_eq!(m.collector_at(&even), ("Testerin", "Alice"));
+        m.co_holders.push(CoHolder {
+            surname: "Nachbarin".to_string(),
+            forename: "Nora".to_string(),
+            schedule: Schedule::Even,
+        });
+        assert!(m.is_holder("testerin", "alice"));
+        assert!(m.is_holder("Nachbarin", "nora"));
+        assert!(!m.is_holder("Nachbarin", "Alice"));
+        assert_eq!(m.collector_at(&odd), ("Testerin", "Alice"));
+        assert_eq!(m.collector_at(&even), ("Nachbarin", "Nora"));
+        let mut joker = gen_joker_a();
+        (joker.surname, joker.forename) =
+            ("Nachbarin".to_string(), "Nora".to_string());
+        assert!(has_joker(&m, &[joker]));
+    }
+    #[test]
+    fn test_count_shares() {
+        let shares = count_shares(&gen_members());
+        assert_eq!(shares, Shares::from([(BIG, 5), (SMALL, 5)]));
+        // Heads and shares differ for members with several shares
+        assert_eq!(
+            filter_members_by_share(&gen_members().to_vec(), BIG).len(),
+            2
+        );
+        assert_eq!(count_shares(&[]), Shares::default());
+    }
+    #[test]
+    fn test_shares() {
+        let mut a = Shares::from([(BIG, 2), (SMALL, 0)]);
+        let b = Shares::from([(BIG, 2), ("bread", 0)]);
+        assert_eq!(a, b);
+        assert_eq!(a.to_string(), "big 2, small 0");
+        assert_eq!(a.amounts(), "2 0");
+        assert_eq!(Shares::default().to_string(), "none");
+        a += &Shares::from([("bread", 1), (SMALL, 3)]);
+        assert_eq!(a.get("bread"), 1);
+        assert_eq!(a.types(), vec!["big", "bread", "small"]);
+        assert_eq!(
+            a.differences(&b),
+            vec![("bread".to_string(), 1, 0), ("small".to_string(), 3, 0)]
+        );
+        assert!(!a.is_empty());
+        assert!(Shares::from([(BIG, 0)]).is_empty());
+        assert_eq!(share_title("half"), "Half");
+    }
+    #[test]
+    fn test_share_types() {
+        let types = share_types(None);
+        assert_eq!(types.len(), 2);
+        assert_eq!(types[1].name, SMALL);
+        assert_eq!(types[1].member_column, 6);
+        let bread = vec![ShareType {
+            name: "bread".to_string(),
+            member_column: 21,
+            joker_column: 7,
+            tickoff_column: 9,
+        }];
+        assert_eq!(share_types(Some(&bread)), bread);
+    }
+    #[test]
+    fn new_member_share_types() {
+        let m = Member::new(
+            &Data::String("EV".to_string()),
+            &Data::Int(87),
+            &Data::String("Smith".to_string()),
+            &Data::String("John".to_string()),
+            &[(BIG, &Data::Int(1)), ("bread", &Data::Int(2))],
+            &Data::String("Perouse".to_string()),
+            &Data::String("aktiv".to_string()),
+            &status_map(None),
+            &Data::Empty,
+            &Data::Empty,
+            &Data::Empty,
+            77,
+        )
+        .unwrap();
+        assert_eq!(m.shares, Shares::from([(BIG, 1), ("bread", 2)]));
+        let m = Member::new(
+            &Data::String("EV".to_string()),
+            &Data::Int(87),
+            &Data::String("Smith".to_string()),
+            &Data::String("John".to_string()),
+            &[("bread", &Data::Empty)],
+            &Data::String("Perouse".to_string()),
+            &Data::String("aktiv".to_string()),
+            &status_map(None),
+            &Data::Empty,
+            &Data::Empty,
+            &Data::Empty,
+            77,
+        );
+        assert_eq!(
+            m.unwrap_err().to_string(),
+            "Cannot parse value for bread amount in line 77"
+        );
+    }
+    #[test]
     fn test_check_member_list() {
-        let warnings = check_member_list(&gen_members());
+        let mut reporter = MemoryReporter::default();
+        let warnings = check_member_list(&gen_members(), &mut reporter);
         assert_eq!(warnings, 0);
+        assert!(reporter.findings.is_empty());
     }
     #[test]
     fn test_check_member_list_fail() {
-        let warnings = check_member_list(&gen_members_fail());
+        let mut reporter = MemoryReporter::default();
+        let warnings = check_member_list(&gen_members_fail(), &mut reporter);
         assert_eq!(warnings, 6);
+        assert_eq!(reporter.count(Severity::Warning), 6);
+    }
+
+    #[test]
+    fn test_diff_members() {
+        let old = gen_members().to_vec();
+        assert!(diff_members(&old, &old).is_empty());
+
+        let mut new = old.clone();
+        new.remove(0);
+        new[0].shares.set(BIG, 1);
+        new[0].location = Location::Gerlingen;
+        new[1].status = MemberStatus::Inactive;
+        new[1].surname = "Testeres".to_string();
+        new.push(Member::new_from_values(
+            "EV-4",
+            4,
+            "Neu",
+            "Nina",
+            1,
+            0,
+            Location::Leonberg,
+            MemberStatus::Active,
+        ));
+        let changes = diff_members(&old, &new);
+        let texts: Vec<String> =
+            changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Changed shares of EV-2 Tester Bob from big 0, small 2 to big 1, small 2",
+                "Moved EV-2 Tester Bob from Perouse to Gerlingen",
+                "Contract EV-3 Testeres Cloe is now inactive",
+                "Renamed EV-3 Testeress Cloe to Testeres Cloe",
+                "New contract EV-4 Neu Nina at Leonberg, big 1, small 0",
+                "Removed contract EV-1 Testerin Alice at Perouse",
+            ]
+        );
     }
 }
@@ -0,0 +1,210 @@
+use crate::joker;
+use crate::location::Location;
+use crate::member;
+use crate::report::Reporter;
+use anyhow::{Result, anyhow};
+use rust_xlsxwriter::{Format, Workbook};
+use strum::IntoEnumIterator;
+
+/// Crates to pack for one location.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackingItem {
+    pub location: Location,
+    pub shares: member::Shares,
+}
+
+/// Container type for the PackingList
+pub type PackingList = Vec<PackingItem>;
+
+/// Compute the crates to pack per location at the given date.
+/// Members with a joker at this date are not packed for.
+pub fn packing(
+    active_members: &[member::Member],
+    jokers: &[joker::Joker],
+    date: &chrono::NaiveDate,
+) -> PackingList {
+    let weekly_jokers = joker::filter_jokers_by_date(jokers, date);
+    let collectors = member::filter_jokers(active_members, &weekly_jokers);
+    Location::iter()
+        .filter(|l| *l != Location::NotParsed)
+        .map(|location| {
+            let loc =
+                member::filter_members_by_location(&collectors, &location);
+            PackingItem {
+                location,
+                shares: member::count_shares(&loc),
+            }
+        })
+        .collect()
+}
+
+/// Helper function to get the crates over all locations
+pub fn get_total(packing: &[PackingItem]) -> member::Shares {
+    let mut total = member::Shares::default();
+    for item in packing {
+        total += &item.shares;
+    }
+    total
+}
+
+/// Report the packing list as table, one column per share type
+pub fn print_packing(packing: &[PackingItem], reporter: &mut dyn Reporter) {
+    let total = get_total(packing);
+    let types = total.types();
+    let row = |name: String, shares: &member::Shares| {
+        let mut line = format!("{name:<16}");
+        for t in types.iter() {
+            line.push_str(&format!("{:>8}", shares.get(t)));
+        }
+        line
+    };
+    let mut header = format!("{:<16}", "Location");
+    for t in types.iter() {
+        header.push_str(&format!("{:>8}", member::share_title(t)));
+    }
+    reporter.message(&header);
+    for item in packing {
+        reporter.message(&row(format!("{:?}", item.location), &item.shares));
+    }
+    reporter.message(&row("Total".to_string(), &total));
+}
+
+/// Write the packing list as CSV file
+pub fn write_packing_csv(packing: &[PackingItem], file: &str) -> Result<()> {
+    let mut writer = csv::Writer::from_path(file).map_err(|e| {
+        anyhow!(format!("Error {e} while creating packing file {file}"))
+    })?;
+    let total = get_total(packing);
+    let types = total.types();
+    let row = |name: String, shares: &member::Shares| {
+        let mut record = vec![name];
+        record.extend(types.iter().map(|t| shares.get(t).to_string()));
+        record
+    };
+    let mut header = vec!["Location".to_string()];
+    header.extend(types.iter().map(|t| member::share_title(t)));
+    writer.write_record(header)?;
+    for item in packing {
+        writer
+            .write_record(row(format!("{:?}", item.location), &item.shares))?;
+    }
+    writer.write_record(row("Total".to_string(), &total))?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write the packing list as xlsx file
+pub fn write_packing_xlsx(packing: &[PackingItem], file: &str) -> Result<()> {
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Packliste")?;
+    let total = get_total(packing);
+    let types = total.types();
+    sheet.write_string_with_format(0, 0, "Location", &bold)?;
+    for (col, t) in (1..).zip(types.iter()) {
+        sheet.write_string_with_format(
+            0,
+            col,
+            member::share_title(t),
+            &bold,
+        )?;
+    }
+    let mut row = 1;
+    for item in packing {
+        sheet.write_string(row, 0, format!("{:?}", item.location))?;
+        for (col, t) in (1..).zip(types.iter()) {
+            sheet.write_number(row, col, item.shares.get(t))?;
+        }
+        row += 1;
+    }
+    sheet.write_string_with_format(row, 0, "Total", &bold)?;
+    for (col, t) in (1..).zip(types.iter()) {
+        sheet.write_number_with_format(row, col, total.get(t), &bold)?;
+    }
+    workbook.save(file).map_err(|e| {
+        anyhow!(format!("Error {e} while writing packing file {file}"))
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod packing_tests {
+
+    use super::*;
+    use crate::member::{BIG, SMALL};
+    use crate::report::MemoryReporter;
+    use crate::test_common::test_common::*;
+    use calamine::{Reader, Xlsx, open_workbook};
+    use chrono::NaiveDate;
+
+    fn gen_packing() -> PackingList {
+        let mut members = gen_members().to_vec();
+        members[2].location = Location::Gerlingen;
+        let date = NaiveDate::from_ymd_opt(1, 1, 1).unwrap();
+        packing(&members, &[gen_joker_b()], &date)
+    }
+
+    #[test]
+    fn test_packing() {
+        let packing = gen_packing();
+        assert_eq!(packing.len(), 6);
+        assert_eq!(packing[0].location, Location::Perouse);
+        assert_eq!(
+            packing[0].shares,
+            member::Shares::from([(BIG, 2), (SMALL, 0)])
+        );
+        assert_eq!(packing[1].location, Location::Gerlingen);
+        assert_eq!(
+            packing[1].shares,
+            member::Shares::from([(BIG, 3), (SMALL, 3)])
+        );
+        assert_eq!(
+            get_total(&packing),
+            member::Shares::from([(BIG, 5), (SMALL, 3)])
+        );
+    }
+
+    #[test]
+    fn test_packing_other_date() {
+        let date = NaiveDate::from_ymd_opt(2025, 11, 7).unwrap();
+        let packing = packing(&gen_members(), &[gen_joker_b()], &date);
+        assert_eq!(
+            get_total(&packing),
+            member::Shares::from([(BIG, 5), (SMALL, 5)])
+        );
+    }
+
+    #[test]
+    fn test_print_packing() {
+        let mut reporter = MemoryReporter::default();
+        print_packing(&gen_packing(), &mut reporter);
+        assert_eq!(reporter.messages.len(), 8);
+        assert_eq!(reporter.messages[7], "Total                  5       3");
+    }
+
+    #[test]
+    fn test_write_packing_csv() {
+        let file = std::env::temp_dir().join("somato_test_packing.csv");
+        let file = file.to_str().unwrap();
+        write_packing_csv(&gen_packing(), file).unwrap();
+        let content = std::fs::read_to_string(file).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[0], "Location,Big,Small");
+        assert_eq!(lines[1], "Perouse,2,0");
+        assert_eq!(lines[7], "Total,5,3");
+    }
+
+    #[test]
+    fn test_write_packing_xlsx() {
+        let file = std::env::temp_dir().join("somato_test_packing.xlsx");
+        let file = file.to_str().unwrap();
+        write_packing_xlsx(&gen_packing(), file).unwrap();
+        let mut excel: Xlsx<_> = open_workbook(file).unwrap();
+        let range = excel.worksheet_range("Packliste").unwrap();
+        assert_eq!(range.get_size(), (8, 3));
+        assert_eq!(range.get_value((2, 0)).unwrap().to_string(), "Gerlingen");
+        assert_eq!(range.get_value((7, 1)).unwrap().to_string(), "5");
+    }
+}
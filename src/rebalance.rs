@@ -0,0 +1,290 @@
+use crate::capacity::{self, Capacity};
+use crate::location::Location;
+use crate::member::Member;
+use crate::report::Reporter;
+use serde::Deserialize;
+use std::fmt;
+use strum::IntoEnumIterator;
+
+/// Distance between two neighbouring depots, the same in both directions.
+/// Depots without a distance are not neighbours. The distance can as well
+/// be a preference, smaller values are preferred.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Distance {
+    pub from: Location,
+    pub to: Location,
+    pub distance: u32,
+}
+
+/// Distance between the depots if they are neighbours
+pub fn distance(
+    distances: &[Distance],
+    from: &Location,
+    to: &Location,
+) -> Option<u32> {
+    distances
+        .iter()
+        .find(|d| {
+            (d.from == *from && d.to == *to) || (d.from == *to && d.to == *from)
+        })
+        .map(|d| d.distance)
+}
+
+/// A member that should collect at another depot
+#[derive(Debug, Clone, PartialEq)]
+pub struct Proposal {
+    pub contract_no: String,
+    pub surname: String,
+    pub forename: String,
+    pub from: Location,
+    pub to: Location,
+    pub distance: u32,
+}
+
+impl fmt::Display for Proposal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Move {} {} {} from {:?} to {:?} (distance {})",
+            self.contract_no,
+            self.surname,
+            self.forename,
+            self.from,
+            self.to,
+            self.distance
+        )
+    }
+}
+
+/// Load of a depot, collectors relative to its capacity.
+/// A depot without collector capacity is taken to hold the average.
+fn load(count: usize, capacity: Option<&Capacity>, average: f64) -> f64 {
+    let size = capacity
+        .and_then(|c| c.collectors)
+        .map(|c| c as f64)
+        .unwrap_or(average);
+    if size > 0.0 {
+        count as f64 / size
+    } else {
+        f64::INFINITY
+    }
+}
+
+/// Propose moves of members between neighbouring depots until no move
+/// evens out the load any more. The members are taken as given, e.g. the
+/// active members at a date.
+/// A move never fills a depot beyond its collector or share capacity and
+/// never turns the load around. Among the possible moves the one between the
+/// depots with the biggest difference in load is taken, then the one with the
+/// smaller distance. From a depot the member with the fewest shares moves
+/// first; members with co-holders or planned moves are left alone.
+pub fn propose_moves(
+    members: &[Member],
+    capacities: &[Capacity],
+    distances: &[Distance],
+) -> Vec<Proposal> {
+    let locations: Vec<Location> = Location::iter()
+        .filter(|l| *l != Location::NotParsed)
+        .collect();
+    let size = |m: &Member| m.shares.iter().map(|(_, a)| a).sum::<u32>();
+    let mut counts: Vec<usize> = locations
+        .iter()
+        .map(|l| members.iter().filter(|m| m.location == *l).count())
+        .collect();
+    let mut shares: Vec<u32> = locations
+        .iter()
+        .map(|l| members.iter().filter(|m| m.location == *l).map(size).sum())
+        .collect();
+    let average = counts.iter().sum::<usize>() as f64 / locations.len() as f64;
+    let mut movable: Vec<&Member> = members
+        .iter()
+        .filter(|m| m.co_holders.is_empty() && m.moves.is_empty())
+        .collect();
+    movable.sort_by(|a, b| {
+        size(a)
+            .cmp(&size(b))
+            .then(a.contract_no.cmp(&b.contract_no))
+    });
+    let capacity = |i: usize| capacity::capacity_for(capacities, &locations[i]);
+    // Depots with only members that stay
+    let mut fixed = vec![false; locations.len()];
+    let mut proposals = Vec::new();
+    loop {
+        let mut best: Option<(f64, u32, usize, usize)> = None;
+        for a in 0..locations.len() {
+            for b in 0..locations.len() {
+                let Some(d) = distance(distances, &locations[a], &locations[b])
+                else {
+                    continue;
+                };
+                if a == b || counts[a] == 0 || fixed[a] {
+                    continue;
+                }
+                if capacity(b)
+                    .and_then(|c| c.collectors)
+                    .is_some_and(|max| counts[b] + 1 > max)
+                {
+                    continue;
+                }
+                // The member with the fewest shares would move
+                let Some(mover) =
+                    movable.iter().find(|m| m.location == locations[a])
+                else {
+                    fixed[a] = true;
+                    continue;
+                };
+                if capacity(b)
+                    .and_then(|c| c.shares)
+                    .is_some_and(|max| shares[b] + size(mover) > max)
+                {
+                    continue;
+                }
+                let after_a = load(counts[a] - 1, capacity(a), average);
+                let after_b = load(counts[b] + 1, capacity(b), average);
+                if after_a < after_b {
+                    continue;
+                }
+                let gap = load(counts[a], capacity(a), average)
+                    - load(counts[b], capacity(b), average);
+                let better = best.is_none_or(|(g, bd, _, _)| {
+                    gap > g + f64::EPSILON
+                        || ((gap - g).abs() <= f64::EPSILON && d < bd)
+                });
+                if better {
+                    best = Some((gap, d, a, b));
+                }
+            }
+        }
+        let Some((_, d, a, b)) = best else {
+            break;
+        };
+        let Some(index) =
+            movable.iter().position(|m| m.location == locations[a])
+        else {
+            fixed[a] = true;
+            continue;
+        };
+        let m = movable.remove(index);
+        proposals.push(Proposal {
+            contract_no: m.contract_no.clone(),
+            surname: m.surname.clone(),
+            forename: m.forename.clone(),
+            from: locations[a].clone(),
+            to: locations[b].clone(),
+            distance: d,
+        });
+        counts[a] -= 1;
+        counts[b] += 1;
+        shares[a] -= size(m);
+        shares[b] += size(m);
+    }
+    proposals
+}
+
+/// Report the proposed moves
+pub fn report_proposals(proposals: &[Proposal], reporter: &mut dyn Reporter) {
+    reporter.message(&format!("Proposed moves: {}", proposals.len()));
+    for p in proposals.iter() {
+        reporter.message(&format!("  {p}"));
+    }
+}
+
+#[cfg(test)]
+mod rebalance_tests {
+
+    use super::*;
+    use crate::member::MemberStatus;
+    use crate::report::MemoryReporter;
+
+    fn gen_depot(location: Location, count: usize) -> Vec<Member> {
+        (0..count)
+            .map(|i| {
+                Member::new_from_values(
+                    &format!("EV-{:?}-{i}", location),
+                    i as u32,
+                    &format!("Member{i}"),
+                    "Test",
+                    1,
+                    0,
+                    location.clone(),
+                    MemberStatus::Active,
+                )
+            })
+            .collect()
+    }
+
+    fn gen_distance(from: Location, to: Location, distance: u32) -> Distance {
+        Distance { from, to, distance }
+    }
+
+    #[test]
+    fn test_distance() {
+        let distances =
+            [gen_distance(Location::Perouse, Location::Leonberg, 5)];
+        let d = |a, b| distance(&distances, &a, &b);
+        assert_eq!(d(Location::Leonberg, Location::Perouse), Some(5));
+        assert_eq!(d(Location::Perouse, Location::Leonberg), Some(5));
+        assert_eq!(d(Location::Perouse, Location::Gerlingen), None);
+    }
+
+    #[test]
+    fn test_propose_moves() {
+        let mut members = gen_depot(Location::Perouse, 6);
+        members.extend(gen_depot(Location::Leonberg, 2));
+        let distances =
+            [gen_distance(Location::Perouse, Location::Leonberg, 5)];
+        let proposals = propose_moves(&members, &[], &distances);
+        assert_eq!(proposals.len(), 2);
+        assert_eq!(proposals[0].from, Location::Perouse);
+        assert_eq!(proposals[0].to, Location::Leonberg);
+
+        // No neighbours, no moves
+        assert!(propose_moves(&members, &[], &[]).is_empty());
+
+        // Leonberg is full
+        let capacities = [Capacity {
+            location: Location::Leonberg,
+            collectors: Some(3),
+            shares: None,
+        }];
+        let proposals = propose_moves(&members, &capacities, &distances);
+        assert_eq!(proposals.len(), 1);
+
+        // Only the shares of Leonberg are limited
+        let capacities = [Capacity {
+            location: Location::Leonberg,
+            collectors: None,
+            shares: Some(2),
+        }];
+        assert!(propose_moves(&members, &capacities, &distances).is_empty());
+        let capacities = [Capacity {
+            location: Location::Leonberg,
+            collectors: None,
+            shares: Some(3),
+        }];
+        let proposals = propose_moves(&members, &capacities, &distances);
+        assert_eq!(proposals.len(), 1);
+    }
+
+    #[test]
+    fn test_propose_moves_prefers_near_depot() {
+        let mut members = gen_depot(Location::Perouse, 4);
+        members.extend(gen_depot(Location::Leonberg, 1));
+        members.extend(gen_depot(Location::Renningen, 1));
+        let distances = [
+            gen_distance(Location::Perouse, Location::Leonberg, 5),
+            gen_distance(Location::Perouse, Location::Renningen, 2),
+        ];
+        let proposals = propose_moves(&members, &[], &distances);
+        assert_eq!(proposals[0].to, Location::Renningen);
+        assert_eq!(proposals.len(), 2);
+        let mut reporter = MemoryReporter::default();
+        report_proposals(&proposals, &mut reporter);
+        assert_eq!(reporter.messages[0], "Proposed moves: 2");
+        assert_eq!(
+            reporter.messages[1],
+            "  Move EV-Perouse-0 Member0 Test from Perouse to Renningen \
+             (distance 2)"
+        );
+    }
+}
@@ -0,0 +1,338 @@
+use crate::input;
+use crate::joker::Joker;
+use crate::location::Location;
+use crate::member::{self, ShareType, Shares};
+use crate::report::{Reporter, SourceRef};
+use crate::tickoff::TickOffItem;
+use anyhow::{Result, anyhow};
+use calamine::{Data, DataType};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Name of the sheet with the redistributions
+pub const REDISTRIBUTION_SHEET: &str = "Umverteilung";
+
+/// Jokered shares of one depot that are handed out to a recipient, e.g. a
+/// food-sharing point or the members of another depot. The recipient is
+/// listed in the tickoff list of the depot that hands the shares out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Redistribution {
+    pub date: NaiveDate,
+    /// Depot where the jokers were set
+    pub from: Location,
+    /// Depot that hands the shares out
+    pub to: Location,
+    /// Name of the recipient as written in the tickoff list, "Surname, N."
+    pub recipient: String,
+    pub shares: Shares,
+    pub line: u32,
+}
+
+pub type RedistributionList = Vec<Redistribution>;
+
+impl Redistribution {
+    /// Cell of this redistribution in the redistribution sheet
+    pub fn source(&self, column: Option<u32>) -> SourceRef {
+        SourceRef::new(REDISTRIBUTION_SHEET, self.line, column)
+    }
+}
+
+impl fmt::Display for Redistribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} from {:?} at {:?}: {}",
+            self.recipient, self.from, self.to, self.shares
+        )
+    }
+}
+
+/// What happened to the jokered shares of one depot
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RedistributionSummary {
+    /// Shares of the jokers set at the depot
+    pub jokered: Shares,
+    /// Jokered shares of the depot that are handed out elsewhere or donated
+    pub given: Shares,
+    /// Redistributed shares the depot hands out
+    pub received: Shares,
+}
+
+impl RedistributionSummary {
+    /// Jokered shares that are not redistributed
+    pub fn unused(&self) -> Shares {
+        let mut unused = Shares::default();
+        for (t, amount) in self.jokered.iter() {
+            unused.set(t, amount.saturating_sub(self.given.get(t)));
+        }
+        unused
+    }
+
+    /// Check if nothing was jokered or redistributed
+    pub fn is_empty(&self) -> bool {
+        self.jokered.is_empty()
+            && self.given.is_empty()
+            && self.received.is_empty()
+    }
+}
+
+/// Read the redistributions.
+/// Columns: date, from depot, to depot, recipient and the amounts in the
+/// order of the share types.
+pub fn read_redistributions(
+    redistribution_file: &str,
+    format: Option<input::InputFormat>,
+    share_types: &[ShareType],
+) -> Result<RedistributionList> {
+    let mut redistributions = Vec::new();
+    let Some(r) =
+        input::read_sheet(redistribution_file, REDISTRIBUTION_SHEET, format)?
+    else {
+        return Ok(redistributions);
+    };
+    for (line, row) in (2..).zip(r.rows().skip(1)) {
+        if (0..4).any(|x| row.get(x).is_none_or(|c| c.is_empty())) {
+            continue;
+        }
+        let text = |x: usize| {
+            row[x].as_string().unwrap_or_default().trim().to_string()
+        };
+        let location = |x: usize| {
+            Location::parse(&text(x)).map_err(|e| {
+                anyhow!(format!("Error {e} in redistribution line {line}"))
+            })
+        };
+        let date = member::parse_date_cell(&row[0], line)?.ok_or(anyhow!(
+            format!("Missing date in redistribution line {line}")
+        ))?;
+        let mut shares = Shares::default();
+        for (column, t) in (4..).zip(share_types.iter()) {
+            let amount = row.get(column).unwrap_or(&Data::Empty);
+            let amount = match amount {
+                Data::Empty => 0,
+                _ => amount.as_i64().ok_or(anyhow!(format!(
+                    "Cannot parse value for {} amount in redistribution \
+                     line {line}",
+                    t.name
+                )))? as u32,
+            };
+            shares.set(&t.name, amount);
+        }
+        redistributions.push(Redistribution {
+            date,
+            from: location(1)?,
+            to: location(2)?,
+            recipient: text(3),
+            shares,
+            line,
+        });
+    }
+    Ok(redistributions)
+}
+
+/// Return the redistributions of the given date
+pub fn filter_redistributions_by_date(
+    redistributions: &[Redistribution],
+    date: &NaiveDate,
+) -> RedistributionList {
+    redistributions
+        .iter()
+        .filter(|r| r.date == *date)
+        .cloned()
+        .collect()
+}
+
+/// Overview of the jokered, given and received shares of the depot.
+/// Jokers and redistributions have to be of the same date.
+pub fn redistribution_summary(
+    jokers: &[Joker],
+    redistributions: &[Redistribution],
+    location: &Location,
+) -> RedistributionSummary {
+    let mut summary = RedistributionSummary::default();
+    for j in jokers.iter().filter(|j| j.location == *location) {
+        summary.jokered += &j.shares;
+    }
+    for r in redistributions.iter() {
+        if r.from == *location {
+            summary.given += &r.shares;
+        }
+        if r.to == *location {
+            summary.received += &r.shares;
+        }
+    }
+    summary
+}
+
+/// Warn when a depot redistributes more shares than were jokered.
+/// Returns the amount of warnings.
+pub fn check_redistribution(
+    summary: &RedistributionSummary,
+    reporter: &mut dyn Reporter,
+) -> usize {
+    let mut warnings = 0;
+    for (t, given) in summary.given.iter() {
+        let jokered = summary.jokered.get(t);
+        if given > jokered {
+            reporter.warning(format!(
+                "Redistributed {given} {t} shares but only {jokered} are \
+                 jokered"
+            ));
+            warnings += 1;
+        }
+    }
+    warnings
+}
+
+/// Take the recipients of the redistributions out of the tickoff list, so
+/// the member checks do not see them.
+/// Missing recipients and recipients with other amounts are reported.
+/// Returns the remaining list, the recipient entries and the amount of
+/// warnings.
+pub fn split_recipients(
+    tick_off: &[TickOffItem],
+    received: &[Redistribution],
+    reporter: &mut dyn Reporter,
+) -> (Vec<TickOffItem>, Vec<TickOffItem>, usize) {
+    let mut warnings = 0;
+    let is_recipient = |t: &TickOffItem| {
+        received
+            .iter()
+            .any(|r| t.name.trim().to_lowercase() == r.recipient.to_lowercase())
+    };
+    let (recipients, members): (Vec<TickOffItem>, Vec<TickOffItem>) =
+        tick_off.iter().cloned().partition(|t| is_recipient(t));
+    let mut names: Vec<String> = received
+        .iter()
+        .map(|r| r.recipient.to_lowercase())
+        .collect();
+    names.sort();
+    names.dedup();
+    for name in names.iter() {
+        // Several redistributions to one recipient share one entry
+        let batch: Vec<&Redistribution> = received
+            .iter()
+            .filter(|r| r.recipient.to_lowercase() == *name)
+            .collect();
+        let mut expected = Shares::default();
+        for r in batch.iter() {
+            expected += &r.shares;
+        }
+        let r = batch[0];
+        match recipients
+            .iter()
+            .find(|t| t.name.trim().to_lowercase() == *name)
+        {
+            None => {
+                reporter.warning_at(
+                    format!(
+                        "Recipient \"{}\" of redistribution line {} is \
+                         missing in tickoff list",
+                        r.recipient, r.line
+                    ),
+                    Some(r.source(Some(3))),
+                );
+                warnings += 1;
+            }
+            Some(t) if t.shares != expected => {
+                reporter.warning_at(
+                    format!(
+                        "Tickoff amount of recipient \"{}\" does not match \
+                         redistribution: {} {}",
+                        r.recipient, expected, t.shares
+                    ),
+                    t.source.clone(),
+                );
+                warnings += 1;
+            }
+            Some(_) => {}
+        }
+    }
+    (members, recipients, warnings)
+}
+
+#[cfg(test)]
+mod redistribution_tests {
+
+    use super::*;
+    use crate::member::{BIG, SMALL};
+    use crate::report::{MemoryReporter, Severity};
+    use crate::test_common::test_common::*;
+
+    fn gen_redistribution(to: Location, big: u32) -> Redistribution {
+        Redistribution {
+            date: NaiveDate::from_ymd_opt(1, 1, 1).unwrap(),
+            from: Location::Perouse,
+            to,
+            recipient: "Tafel, T.".to_string(),
+            shares: Shares::from([(BIG, big), (SMALL, 0)]),
+            line: 2,
+        }
+    }
+
+    #[test]
+    fn test_redistribution_summary() {
+        let jokers = [gen_joker_a(), gen_joker_b()];
+        let list = [gen_redistribution(Location::Gerlingen, 1)];
+        let perouse =
+            redistribution_summary(&jokers, &list, &Location::Perouse);
+        assert_eq!(perouse.jokered, Shares::from([(BIG, 2), (SMALL, 2)]));
+        assert_eq!(perouse.given, Shares::from([(BIG, 1)]));
+        assert!(perouse.received.is_empty());
+        assert_eq!(perouse.unused(), Shares::from([(BIG, 1), (SMALL, 2)]));
+        let gerlingen =
+            redistribution_summary(&jokers, &list, &Location::Gerlingen);
+        assert!(gerlingen.jokered.is_empty());
+        assert_eq!(gerlingen.received, Shares::from([(BIG, 1)]));
+    }
+
+    #[test]
+    fn test_check_redistribution() {
+        let jokers = [gen_joker_a()];
+        let mut reporter = MemoryReporter::default();
+        let list = [gen_redistribution(Location::Gerlingen, 2)];
+        let summary =
+            redistribution_summary(&jokers, &list, &Location::Perouse);
+        assert_eq!(check_redistribution(&summary, &mut reporter), 0);
+        let list = [gen_redistribution(Location::Gerlingen, 3)];
+        let summary =
+            redistribution_summary(&jokers, &list, &Location::Perouse);
+        assert_eq!(check_redistribution(&summary, &mut reporter), 1);
+        assert_eq!(reporter.count(Severity::Warning), 1);
+    }
+
+    #[test]
+    fn test_split_recipients() {
+        let [a, b, _c] = gen_toi_ok();
+        let recipient = TickOffItem {
+            name: "Tafel, T.".to_string(),
+            shares: Shares::from([(BIG, 3)]),
+            source: None,
+        };
+        let tick_off = vec![a.clone(), recipient.clone(), b.clone()];
+        let mut reporter = MemoryReporter::default();
+
+        // Two redistributions share the entry of the recipient
+        let received = [
+            gen_redistribution(Location::Perouse, 1),
+            gen_redistribution(Location::Perouse, 2),
+        ];
+        let (members, recipients, warnings) =
+            split_recipients(&tick_off, &received, &mut reporter);
+        assert_eq!(members, vec![a.clone(), b.clone()]);
+        assert_eq!(recipients, vec![recipient.clone()]);
+        assert_eq!(warnings, 0);
+
+        let (_, _, warnings) =
+            split_recipients(&tick_off, &received[..1], &mut reporter);
+        assert_eq!(warnings, 1);
+
+        let (members, recipients, warnings) =
+            split_recipients(&tick_off[..1], &received, &mut reporter);
+        assert_eq!(members, vec![a]);
+        assert!(recipients.is_empty());
+        assert_eq!(warnings, 1);
+        assert_eq!(reporter.count(Severity::Warning), 2);
+    }
+}
@@ -0,0 +1,640 @@
+use anyhow::{Result, anyhow};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+
+/// Severity of a finding
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// Verbosity of the output, everything below the level is dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Detailed trace of what was parsed and checked
+    Debug,
+    /// Verdicts and findings
+    Info,
+    /// Only warnings and errors
+    Warn,
+    /// Nothing at all
+    Off,
+}
+
+impl Level {
+    pub fn parse(level: &str) -> Result<Level> {
+        let level = match level.to_lowercase().as_str() {
+            "debug" => Level::Debug,
+            "info" => Level::Info,
+            "warn" => Level::Warn,
+            "off" => Level::Off,
+            _ => return Err(anyhow!("Cannot parse log level {level}")),
+        };
+        Ok(level)
+    }
+
+    /// Level of the command line, then of the environment variable
+    /// SOMATO_LOG, Info if none is given
+    pub fn select(args: Option<Level>, env: Option<&str>) -> Result<Level> {
+        match (args, env) {
+            (Some(level), _) => Ok(level),
+            (None, Some(env)) => Level::parse(env),
+            (None, None) => Ok(Level::Info),
+        }
+    }
+}
+
+/// Cell of an input sheet a finding refers to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SourceRef {
+    pub sheet: String,
+    /// Row as shown in the spreadsheet, starting at 1
+    pub row: u32,
+    /// Column index starting at 0, None refers to the whole row
+    pub column: Option<u32>,
+}
+
+impl SourceRef {
+    pub fn new(sheet: &str, row: u32, column: Option<u32>) -> Self {
+        SourceRef {
+            sheet: sheet.to_string(),
+            row,
+            column,
+        }
+    }
+}
+
+/// Column name as used by spreadsheets, e.g. 0 is "A" and 26 is "AA"
+pub fn column_name(column: u32) -> String {
+    let mut name = String::new();
+    let mut n = column + 1;
+    while n > 0 {
+        let rem = (n - 1) % 26;
+        name.insert(0, (b'A' + rem as u8) as char);
+        n = (n - 1) / 26;
+    }
+    name
+}
+
+impl fmt::Display for SourceRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            Some(c) => {
+                write!(f, "{}!{}{}", self.sheet, column_name(c), self.row)
+            }
+            None => write!(f, "{}!{}:{}", self.sheet, self.row, self.row),
+        }
+    }
+}
+
+/// A single result of a check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+    /// Where the problem is found in the input, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<SourceRef>,
+}
+
+/// A finding together with the section it was reported in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectionFinding {
+    pub section: Option<String>,
+    #[serde(flatten)]
+    pub finding: Finding,
+}
+
+/// Receives everything that somato has to tell.
+/// Library functions report through this trait instead of printing.
+pub trait Reporter {
+    /// Verdicts and summaries
+    fn message(&mut self, text: &str);
+
+    /// Detailed trace, only shown on demand
+    fn debug(&mut self, text: &str);
+
+    /// Results of the checks
+    fn finding(&mut self, finding: Finding);
+
+    /// Start of a new section, e.g. the analysis of one location
+    fn section(&mut self, title: &str);
+
+    /// Called once at the end, e.g. to write collected results
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn info(&mut self, message: String) {
+        self.finding(Finding {
+            severity: Severity::Info,
+            message,
+            source: None,
+        });
+    }
+
+    fn warning(&mut self, message: String) {
+        self.finding(Finding {
+            severity: Severity::Warning,
+            message,
+            source: None,
+        });
+    }
+
+    fn error(&mut self, message: String) {
+        self.finding(Finding {
+            severity: Severity::Error,
+            message,
+            source: None,
+        });
+    }
+
+    /// Warning that refers to a cell of the input
+    fn warning_at(&mut self, message: String, source: Option<SourceRef>) {
+        self.finding(Finding {
+            severity: Severity::Warning,
+            message,
+            source,
+        });
+    }
+
+    /// Error that refers to a cell of the input
+    fn error_at(&mut self, message: String, source: Option<SourceRef>) {
+        self.finding(Finding {
+            severity: Severity::Error,
+            message,
+            source,
+        });
+    }
+}
+
+/// Prints everything colored to the terminal
+#[derive(Debug, Default)]
+pub struct TerminalReporter {}
+
+impl Reporter for TerminalReporter {
+    fn message(&mut self, text: &str) {
+        println!("{text}");
+    }
+
+    fn debug(&mut self, text: &str) {
+        println!("{}", text.dimmed());
+    }
+
+    fn finding(&mut self, finding: Finding) {
+        let text = match &finding.source {
+            Some(source) => format!("    {} ({source})", finding.message),
+            None => format!("    {}", finding.message),
+        };
+        match finding.severity {
+            Severity::Info => println!("{}", text.bright_blue()),
+            Severity::Warning => println!("{}", text.bright_red()),
+            Severity::Error => println!("{}", text.bright_red().bold()),
+        }
+    }
+
+    fn section(&mut self, title: &str) {
+        println!("{}", "*".repeat(80));
+        println!("* {title}");
+        println!("{}", "*".repeat(80));
+    }
+}
+
+/// Discards everything
+#[derive(Debug, Default)]
+pub struct QuietReporter {}
+
+impl Reporter for QuietReporter {
+    fn message(&mut self, _text: &str) {}
+
+    fn debug(&mut self, _text: &str) {}
+
+    fn finding(&mut self, _finding: Finding) {}
+
+    fn section(&mut self, _title: &str) {}
+}
+
+/// Writes everything as plain text, e.g. into a file
+pub struct FileReporter {
+    out: Box<dyn Write>,
+}
+
+impl FileReporter {
+    pub fn new(out: Box<dyn Write>) -> Self {
+        FileReporter { out }
+    }
+
+    /// Create the file, an existing file is overwritten
+    pub fn create(file: &str) -> Result<Self> {
+        let out = File::create(file).map_err(|e| {
+            anyhow!(format!("Error {e} while creating report file {file}"))
+        })?;
+        Ok(Self::new(Box::new(out)))
+    }
+}
+
+impl Reporter for FileReporter {
+    fn message(&mut self, text: &str) {
+        let _ = writeln!(self.out, "{text}");
+    }
+
+    fn debug(&mut self, text: &str) {
+        let _ = writeln!(self.out, "{text}");
+    }
+
+    fn finding(&mut self, finding: Finding) {
+        let _ = match &finding.source {
+            Some(source) => writeln!(
+                self.out,
+                "    {:?}: {} ({source})",
+                finding.severity, finding.message
+            ),
+            None => writeln!(
+                self.out,
+                "    {:?}: {}",
+                finding.severity, finding.message
+            ),
+        };
+    }
+
+    fn section(&mut self, title: &str) {
+        let _ = writeln!(self.out, "* {title}");
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Keeps everything in memory
+#[derive(Debug, Default)]
+pub struct MemoryReporter {
+    pub messages: Vec<String>,
+    pub debug: Vec<String>,
+    pub findings: Vec<SectionFinding>,
+    pub section: Option<String>,
+}
+
+impl MemoryReporter {
+    /// Amount of findings with the given severity
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.finding.severity == severity)
+            .count()
+    }
+}
+
+impl Reporter for MemoryReporter {
+    fn message(&mut self, text: &str) {
+        self.messages.push(text.to_string());
+    }
+
+    fn debug(&mut self, text: &str) {
+        self.debug.push(text.to_string());
+    }
+
+    fn finding(&mut self, finding: Finding) {
+        self.findings.push(SectionFinding {
+            section: self.section.clone(),
+            finding,
+        });
+    }
+
+    fn section(&mut self, title: &str) {
+        self.section = Some(title.to_string());
+    }
+}
+
+/// Passes everything on and keeps a copy of the findings
+pub struct Recorder<'a> {
+    inner: &'a mut dyn Reporter,
+    pub findings: Vec<SectionFinding>,
+    section: Option<String>,
+}
+
+impl<'a> Recorder<'a> {
+    pub fn new(inner: &'a mut dyn Reporter) -> Self {
+        Recorder {
+            inner,
+            findings: Vec::new(),
+            section: None,
+        }
+    }
+}
+
+impl Reporter for Recorder<'_> {
+    fn message(&mut self, text: &str) {
+        self.inner.message(text);
+    }
+
+    fn debug(&mut self, text: &str) {
+        self.inner.debug(text);
+    }
+
+    fn finding(&mut self, finding: Finding) {
+        self.findings.push(SectionFinding {
+            section: self.section.clone(),
+            finding: finding.clone(),
+        });
+        self.inner.finding(finding);
+    }
+
+    fn section(&mut self, title: &str) {
+        self.section = Some(title.to_string());
+        self.inner.section(title);
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.inner.finish()
+    }
+}
+
+/// Collects the findings and writes them as JSON at the end
+pub struct JsonReporter {
+    out: Box<dyn Write>,
+    collected: MemoryReporter,
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    findings: &'a [SectionFinding],
+}
+
+impl JsonReporter {
+    pub fn new(out: Box<dyn Write>) -> Self {
+        JsonReporter {
+            out,
+            collected: MemoryReporter::default(),
+        }
+    }
+}
+
+impl Reporter for JsonReporter {
+    fn message(&mut self, _text: &str) {}
+
+    fn debug(&mut self, _text: &str) {}
+
+    fn finding(&mut self, finding: Finding) {
+        self.collected.finding(finding);
+    }
+
+    fn section(&mut self, title: &str) {
+        self.collected.section(title);
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let report = JsonReport {
+            findings: &self.collected.findings,
+        };
+        serde_json::to_writer_pretty(&mut self.out, &report)?;
+        writeln!(self.out)?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Passes on only what is at least at the given level.
+/// Warnings and errors are shown unless the level is Off.
+pub struct LevelFilter {
+    inner: Box<dyn Reporter>,
+    level: Level,
+}
+
+impl LevelFilter {
+    pub fn new(inner: Box<dyn Reporter>, level: Level) -> Self {
+        LevelFilter { inner, level }
+    }
+}
+
+impl Reporter for LevelFilter {
+    fn message(&mut self, text: &str) {
+        if self.level <= Level::Info {
+            self.inner.message(text);
+        }
+    }
+
+    fn debug(&mut self, text: &str) {
+        if self.level <= Level::Debug {
+            self.inner.debug(text);
+        }
+    }
+
+    fn finding(&mut self, finding: Finding) {
+        let needed = match finding.severity {
+            Severity::Info => Level::Info,
+            Severity::Warning | Severity::Error => Level::Warn,
+        };
+        if self.level <= needed {
+            self.inner.finding(finding);
+        }
+    }
+
+    fn section(&mut self, title: &str) {
+        if self.level <= Level::Warn {
+            self.inner.section(title);
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.inner.finish()
+    }
+}
+
+/// Create the reporter selected on the command line.
+/// JSON is written to the output file if given or to stdout. Otherwise text
+/// goes to the output file or terminal.
+pub fn create_reporter(
+    level: Level,
+    json: bool,
+    output: Option<&str>,
+) -> Result<Box<dyn Reporter>> {
+    let reporter: Box<dyn Reporter> = match (json, output) {
+        (true, Some(file)) => {
+            Box::new(JsonReporter::new(FileReporter::create(file)?.out))
+        }
+        (true, None) => {
+            Box::new(JsonReporter::new(Box::new(std::io::stdout())))
+        }
+        (false, Some(file)) => Box::new(FileReporter::create(file)?),
+        (false, None) => Box::new(TerminalReporter::default()),
+    };
+    Ok(Box::new(LevelFilter::new(reporter, level)))
+}
+
+#[cfg(test)]
+mod report_tests {
+
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Writer that can be inspected after it was handed over
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn text(&self) -> String {
+            String::from_utf8(self.0.borrow().clone()).unwrap()
+        }
+    }
+
+    fn report(reporter: &mut dyn Reporter) {
+        reporter.debug("Tracing");
+        reporter.message("Checking");
+        reporter.warning("First".to_string());
+        reporter.section("Perouse");
+        reporter.error("Second".to_string());
+        reporter.warning_at(
+            "Third".to_string(),
+            Some(SourceRef::new("PER", 9, Some(1))),
+        );
+        reporter.finish().unwrap();
+    }
+
+    #[test]
+    fn test_memory_reporter() {
+        let mut reporter = MemoryReporter::default();
+        report(&mut reporter);
+        assert_eq!(reporter.messages, vec!["Checking"]);
+        assert_eq!(reporter.debug, vec!["Tracing"]);
+        assert_eq!(reporter.findings.len(), 3);
+        assert_eq!(reporter.findings[0].section, None);
+        assert_eq!(reporter.findings[1].section, Some("Perouse".to_string()));
+        assert_eq!(reporter.count(Severity::Warning), 2);
+        assert_eq!(reporter.count(Severity::Error), 1);
+        assert_eq!(reporter.count(Severity::Info), 0);
+    }
+
+    #[test]
+    fn test_file_reporter() {
+        let buffer = SharedBuffer::default();
+        let mut reporter = FileReporter::new(Box::new(buffer.clone()));
+        report(&mut reporter);
+        assert_eq!(
+            buffer.text(),
+            "Tracing\nChecking\n    Warning: First\n* Perouse\n    Error: Second\n    Warning: Third (PER!B9)\n"
+        );
+    }
+
+    #[test]
+    fn test_json_reporter() {
+        let buffer = SharedBuffer::default();
+        let mut reporter = JsonReporter::new(Box::new(buffer.clone()));
+        report(&mut reporter);
+        let json: serde_json::Value =
+            serde_json::from_str(&buffer.text()).unwrap();
+        let findings = json["findings"].as_array().unwrap();
+        assert_eq!(findings.len(), 3);
+        assert_eq!(findings[0]["severity"], "Warning");
+        assert_eq!(findings[0]["message"], "First");
+        assert!(findings[0]["section"].is_null());
+        assert_eq!(findings[1]["section"], "Perouse");
+        assert!(findings[1]["source"].is_null());
+        assert_eq!(findings[2]["source"]["sheet"], "PER");
+        assert_eq!(findings[2]["source"]["row"], 9);
+    }
+
+    #[test]
+    fn test_quiet_reporter() {
+        let mut reporter = QuietReporter::default();
+        report(&mut reporter);
+    }
+
+    #[test]
+    fn test_level_parse() {
+        assert_eq!(Level::parse("debug").unwrap(), Level::Debug);
+        assert_eq!(Level::parse("Info").unwrap(), Level::Info);
+        assert_eq!(Level::parse("WARN").unwrap(), Level::Warn);
+        assert_eq!(Level::parse("off").unwrap(), Level::Off);
+        assert!(Level::parse("loud").is_err());
+    }
+
+    #[test]
+    fn test_level_select() {
+        assert_eq!(Level::select(None, None).unwrap(), Level::Info);
+        assert_eq!(Level::select(None, Some("debug")).unwrap(), Level::Debug);
+        assert_eq!(
+            Level::select(Some(Level::Warn), Some("debug")).unwrap(),
+            Level::Warn
+        );
+        assert!(Level::select(None, Some("loud")).is_err());
+    }
+
+    #[test]
+    fn test_level_filter() {
+        let buffer = SharedBuffer::default();
+        let mut reporter = LevelFilter::new(
+            Box::new(FileReporter::new(Box::new(buffer.clone()))),
+            Level::Warn,
+        );
+        report(&mut reporter);
+        reporter.info("Third".to_string());
+        assert_eq!(
+            buffer.text(),
+            "    Warning: First\n* Perouse\n    Error: Second\n    Warning: Third (PER!B9)\n"
+        );
+
+        let buffer = SharedBuffer::default();
+        let mut reporter = LevelFilter::new(
+            Box::new(FileReporter::new(Box::new(buffer.clone()))),
+            Level::Info,
+        );
+        report(&mut reporter);
+        assert!(buffer.text().starts_with("Checking\n"));
+
+        let buffer = SharedBuffer::default();
+        let mut reporter = LevelFilter::new(
+            Box::new(FileReporter::new(Box::new(buffer.clone()))),
+            Level::Off,
+        );
+        report(&mut reporter);
+        assert!(buffer.text().is_empty());
+    }
+
+    #[test]
+    fn test_source_ref() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(15), "P");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(27), "AB");
+        assert_eq!(
+            SourceRef::new("Ernteverträge", 5, Some(2)).to_string(),
+            "Ernteverträge!C5"
+        );
+        assert_eq!(
+            SourceRef::new("Eingabe", 7, None).to_string(),
+            "Eingabe!7:7"
+        );
+    }
+
+    #[test]
+    fn test_recorder() {
+        let mut inner = MemoryReporter::default();
+        let mut recorder = Recorder::new(&mut inner);
+        report(&mut recorder);
+        let findings = recorder.findings;
+        assert_eq!(findings.len(), 3);
+        assert_eq!(findings[2].section, Some("Perouse".to_string()));
+        assert_eq!(inner.findings, findings);
+        assert_eq!(inner.messages, vec!["Checking"]);
+    }
+}
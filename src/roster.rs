@@ -0,0 +1,268 @@
+use crate::html::escape;
+use crate::joker;
+use crate::location::Location;
+use crate::member;
+use anyhow::{Result, anyhow};
+use rust_xlsxwriter::{Format, FormatBorder, Workbook};
+use serde::Deserialize;
+use std::fmt::Write;
+use std::path::Path;
+use strum::IntoEnumIterator;
+
+/// Output format of the pickup rosters
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RosterFormat {
+    #[default]
+    Html,
+    Xlsx,
+}
+
+/// Members of the location that collect at the given date, sorted by
+/// surname and forename. Shared contracts carry the name of the household
+/// whose turn it is.
+pub fn roster(
+    active_members: &[member::Member],
+    jokers: &[joker::Joker],
+    date: &chrono::NaiveDate,
+    location: &Location,
+) -> member::MemberList {
+    let weekly_jokers = joker::filter_jokers_by_date(jokers, date);
+    let collectors = member::filter_jokers(active_members, &weekly_jokers);
+    let mut roster = member::filter_members_by_location(&collectors, location);
+    for m in roster.iter_mut() {
+        let (surname, forename) = m.collector_at(date);
+        (m.surname, m.forename) = (surname.to_string(), forename.to_string());
+    }
+    roster.sort_by(|a, b| {
+        a.surname
+            .to_lowercase()
+            .cmp(&b.surname.to_lowercase())
+            .then(a.forename.to_lowercase().cmp(&b.forename.to_lowercase()))
+    });
+    roster
+}
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border: 1px solid #444; padding: 0.3em 0.6em; text-align: left; }
+td.number { text-align: right; }
+td.tick { width: 4em; }
+tr.total td { font-weight: bold; }
+@page { size: A4; margin: 1.5cm; }
+";
+
+/// Render the roster of one location as printable HTML page
+pub fn render_roster_html(
+    roster: &[member::Member],
+    location: &Location,
+    date: &chrono::NaiveDate,
+) -> Result<String> {
+    let total = member::count_shares(roster);
+    let types = total.types();
+    let cells = |shares: &member::Shares| {
+        types
+            .iter()
+            .map(|t| format!("<td class=\"number\">{}</td>", shares.get(t)))
+            .collect::<String>()
+    };
+    let mut html = String::new();
+    writeln!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Pickup {location:?} {date}</title>\n<style>{STYLE}</style>\n\
+         </head>\n<body>\n<h1>Pickup {location:?} {date}</h1>"
+    )?;
+    writeln!(
+        html,
+        "<table>\n<tr><th>Surname</th><th>Forename</th>{}\
+         <th>Collected</th></tr>",
+        types
+            .iter()
+            .map(|t| format!("<th>{}</th>", escape(&member::share_title(t))))
+            .collect::<String>()
+    )?;
+    for m in roster.iter() {
+        writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td>{}<td class=\"tick\"></td></tr>",
+            escape(&m.surname),
+            escape(&m.forename),
+            cells(&m.shares)
+        )?;
+    }
+    writeln!(
+        html,
+        "<tr class=\"total\"><td>Total</td><td>{}</td>{}\
+         <td></td></tr>\n</table>\n</body>\n</html>",
+        roster.len(),
+        cells(&total)
+    )?;
+    Ok(html)
+}
+
+/// Write the roster of one location as xlsx file
+pub fn write_roster_xlsx(
+    roster: &[member::Member],
+    location: &Location,
+    date: &chrono::NaiveDate,
+    file: &str,
+) -> Result<()> {
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+    let header = Format::new().set_bold().set_border(FormatBorder::Thin);
+    let cell = Format::new().set_border(FormatBorder::Thin);
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(location.to_short())?;
+    sheet.write_string_with_format(
+        0,
+        0,
+        format!("Pickup {location:?} {date}"),
+        &bold,
+    )?;
+    let total = member::count_shares(roster);
+    let types = total.types();
+    // Share types start at column 2, the tick column follows them
+    let tick = 2 + types.len() as u16;
+    let mut titles = vec!["Surname".to_string(), "Forename".to_string()];
+    titles.extend(types.iter().map(|t| member::share_title(t)));
+    titles.push("Collected".to_string());
+    for (col, title) in (0..).zip(titles.iter()) {
+        sheet.write_string_with_format(2, col, title, &header)?;
+    }
+    let mut row = 3;
+    for m in roster.iter() {
+        sheet.write_string_with_format(row, 0, &m.surname, &cell)?;
+        sheet.write_string_with_format(row, 1, &m.forename, &cell)?;
+        for (col, t) in (2..).zip(types.iter()) {
+            sheet.write_number_with_format(row, col, m.shares.get(t), &cell)?;
+        }
+        sheet.write_blank(row, tick, &cell)?;
+        row += 1;
+    }
+    sheet.write_string_with_format(row, 0, "Total", &bold)?;
+    sheet.write_number_with_format(row, 1, roster.len() as u32, &bold)?;
+    for (col, t) in (2..).zip(types.iter()) {
+        sheet.write_number_with_format(row, col, total.get(t), &bold)?;
+    }
+    sheet.set_column_width(0, 24)?;
+    sheet.set_column_width(1, 20)?;
+    sheet.set_column_width(tick, 12)?;
+    workbook.save(file).map_err(|e| {
+        anyhow!(format!("Error {e} while writing roster file {file}"))
+    })?;
+    Ok(())
+}
+
+/// Write one roster per location into the given directory, e.g.
+/// "roster_PER.html". Returns the written files.
+pub fn write_rosters(
+    active_members: &[member::Member],
+    jokers: &[joker::Joker],
+    date: &chrono::NaiveDate,
+    dir: &str,
+    format: RosterFormat,
+) -> Result<Vec<String>> {
+    let mut files = Vec::new();
+    for location in Location::iter().filter(|l| *l != Location::NotParsed) {
+        let roster = roster(active_members, jokers, date, &location);
+        let extension = match format {
+            RosterFormat::Html => "html",
+            RosterFormat::Xlsx => "xlsx",
+        };
+        let file = Path::new(dir)
+            .join(format!("roster_{}.{extension}", location.to_short()))
+            .to_string_lossy()
+            .to_string();
+        match format {
+            RosterFormat::Html => {
+                let html = render_roster_html(&roster, &location, date)?;
+                std::fs::write(&file, html).map_err(|e| {
+                    anyhow!(format!(
+                        "Error {e} while writing roster file {file}"
+                    ))
+                })?;
+            }
+            RosterFormat::Xlsx => {
+                write_roster_xlsx(&roster, &location, date, &file)?
+            }
+        }
+        files.push(file);
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod roster_tests {
+
+    use super::*;
+    use crate::test_common::test_common::*;
+    use calamine::{Reader, Xlsx, open_workbook};
+    use chrono::NaiveDate;
+
+    fn gen_roster() -> member::MemberList {
+        let date = NaiveDate::from_ymd_opt(1, 1, 1).unwrap();
+        roster(&gen_members(), &[gen_joker_b()], &date, &Location::Perouse)
+    }
+
+    #[test]
+    fn test_roster() {
+        let list = gen_roster();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].surname, "Testeress");
+        assert_eq!(list[1].surname, "Testerin");
+        let date = NaiveDate::from_ymd_opt(2025, 11, 7).unwrap();
+        let list =
+            roster(&gen_members(), &[gen_joker_b()], &date, &Location::Perouse);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list[0].surname, "Tester");
+    }
+
+    #[test]
+    fn test_render_roster_html() {
+        let date = NaiveDate::from_ymd_opt(1, 1, 1).unwrap();
+        let html = render_roster_html(&gen_roster(), &Location::Perouse, &date)
+            .unwrap();
+        assert!(html.contains("<h1>Pickup Perouse 0001-01-01</h1>"));
+        let first = html.find("Testeress").unwrap();
+        let second = html.find("Testerin").unwrap();
+        assert!(first < second);
+        assert!(html.contains(
+            "<tr class=\"total\"><td>Total</td><td>2</td>\
+             <td class=\"number\">5</td><td class=\"number\">3</td>"
+        ));
+    }
+
+    #[test]
+    fn test_write_roster_xlsx() {
+        let date = NaiveDate::from_ymd_opt(1, 1, 1).unwrap();
+        let file = std::env::temp_dir().join("somato_test_roster.xlsx");
+        let file = file.to_str().unwrap();
+        write_roster_xlsx(&gen_roster(), &Location::Perouse, &date, file)
+            .unwrap();
+        let mut excel: Xlsx<_> = open_workbook(file).unwrap();
+        let range = excel.worksheet_range("PER").unwrap();
+        assert_eq!(range.get_value((3, 0)).unwrap().to_string(), "Testeress");
+        assert_eq!(range.get_value((5, 0)).unwrap().to_string(), "Total");
+        assert_eq!(range.get_value((5, 2)).unwrap().to_string(), "5");
+    }
+
+    #[test]
+    fn test_write_rosters() {
+        let date = NaiveDate::from_ymd_opt(1, 1, 1).unwrap();
+        let dir = std::env::temp_dir().join("somato_test_rosters");
+        std::fs::create_dir_all(&dir).unwrap();
+        let files = write_rosters(
+            &gen_members(),
+            &[gen_joker_b()],
+            &date,
+            dir.to_str().unwrap(),
+            RosterFormat::Html,
+        )
+        .unwrap();
+        assert_eq!(files.len(), 6);
+        assert!(files[0].ends_with("roster_PER.html"));
+        assert!(Path::new(&files[5]).exists());
+    }
+}
@@ -0,0 +1,249 @@
+use crate::joker;
+use crate::location::Location;
+use crate::member;
+use crate::report::{Reporter, SectionFinding};
+use crate::tickoff;
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Normalised input and results of one run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub date: NaiveDate,
+    pub members: member::MemberList,
+    /// Jokers set at the date
+    pub jokers: joker::JokerList,
+    pub tick_offs: Vec<(Location, tickoff::TickOffList)>,
+    pub findings: Vec<SectionFinding>,
+}
+
+/// File of the snapshot for the given date, e.g. "<dir>/snapshot_2025-11-07.json"
+pub fn snapshot_file(dir: &str, date: &NaiveDate) -> PathBuf {
+    Path::new(dir).join(format!("snapshot_{date}.json"))
+}
+
+/// Write the snapshot into the directory, a snapshot of the same date is
+/// replaced. Returns the written file.
+pub fn write_snapshot(snapshot: &Snapshot, dir: &str) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir).map_err(|e| {
+        anyhow!(format!("Error {e} while creating snapshot directory {dir}"))
+    })?;
+    let file = snapshot_file(dir, &snapshot.date);
+    let json = serde_json::to_string_pretty(snapshot)?;
+    std::fs::write(&file, json).map_err(|e| {
+        anyhow!(format!(
+            "Error {e} while writing snapshot {}",
+            file.display()
+        ))
+    })?;
+    Ok(file)
+}
+
+pub fn read_snapshot(file: &Path) -> Result<Snapshot> {
+    let json = std::fs::read_to_string(file).map_err(|e| {
+        anyhow!(format!(
+            "Error {e} while reading snapshot {}",
+            file.display()
+        ))
+    })?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// All snapshot files of the directory, the oldest first
+pub fn list_snapshots(dir: &str) -> Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(dir).map_err(|e| {
+        anyhow!(format!("Error {e} while reading snapshot directory {dir}"))
+    })?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name().and_then(|n| n.to_str()).is_some_and(|n| {
+                n.starts_with("snapshot_") && n.ends_with(".json")
+            })
+        })
+        .collect();
+    // The ISO date in the name sorts by time
+    files.sort();
+    Ok(files)
+}
+
+/// Changes between two snapshots
+#[derive(Debug, Clone)]
+pub struct SnapshotDiff {
+    pub old_date: NaiveDate,
+    pub new_date: NaiveDate,
+    pub members: Vec<member::MemberChange>,
+    pub new_findings: Vec<SectionFinding>,
+    pub resolved_findings: Vec<SectionFinding>,
+}
+
+/// Findings of the first list that are not in the second one.
+/// Only severity and message are compared, the cell may have moved.
+fn missing_findings(
+    findings: &[SectionFinding],
+    other: &[SectionFinding],
+) -> Vec<SectionFinding> {
+    findings
+        .iter()
+        .filter(|f| {
+            !other.iter().any(|o| {
+                o.finding.severity == f.finding.severity
+                    && o.finding.message == f.finding.message
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+pub fn diff_snapshots(old: &Snapshot, new: &Snapshot) -> SnapshotDiff {
+    SnapshotDiff {
+        old_date: old.date,
+        new_date: new.date,
+        members: member::diff_members(&old.members, &new.members),
+        new_findings: missing_findings(&new.findings, &old.findings),
+        resolved_findings: missing_findings(&old.findings, &new.findings),
+    }
+}
+
+pub fn report_diff(diff: &SnapshotDiff, reporter: &mut dyn Reporter) {
+    reporter.section(&format!(
+        "Changes from {} to {}",
+        diff.old_date, diff.new_date
+    ));
+    member::report_member_changes(&diff.members, reporter);
+    reporter.message(&format!("New findings: {}", diff.new_findings.len()));
+    for f in diff.new_findings.iter() {
+        reporter.message(&format!(
+            "  {:?}: {}",
+            f.finding.severity, f.finding.message
+        ));
+    }
+    reporter.message(&format!(
+        "Resolved findings: {}",
+        diff.resolved_findings.len()
+    ));
+    for f in diff.resolved_findings.iter() {
+        reporter.message(&format!(
+            "  {:?}: {}",
+            f.finding.severity, f.finding.message
+        ));
+    }
+}
+
+/// Compare the two latest snapshots of the directory
+pub fn diff_latest(
+    dir: &str,
+    reporter: &mut dyn Reporter,
+) -> Result<SnapshotDiff> {
+    let files = list_snapshots(dir)?;
+    let [.., old, new] = files.as_slice() else {
+        return Err(anyhow!("Need at least two snapshots in {dir} for a diff"));
+    };
+    let diff = diff_snapshots(&read_snapshot(old)?, &read_snapshot(new)?);
+    report_diff(&diff, reporter);
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod snapshot_tests {
+
+    use super::*;
+    use crate::report::{Finding, MemoryReporter, Severity};
+    use crate::test_common::test_common::*;
+
+    fn gen_finding(message: &str) -> SectionFinding {
+        SectionFinding {
+            section: None,
+            finding: Finding {
+                severity: Severity::Error,
+                message: message.to_string(),
+                source: None,
+            },
+        }
+    }
+
+    fn gen_snapshot(day: u32) -> Snapshot {
+        Snapshot {
+            date: NaiveDate::from_ymd_opt(2025, 11, day).unwrap(),
+            members: gen_members().to_vec(),
+            jokers: vec![gen_joker_a()],
+            tick_offs: vec![(Location::Perouse, gen_toi_ok().to_vec())],
+            findings: vec![gen_finding("Old problem")],
+        }
+    }
+
+    fn gen_dir(name: &str) -> String {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_write_read_snapshot() {
+        let dir = gen_dir("somato_test_snapshot");
+        let file = write_snapshot(&gen_snapshot(7), &dir).unwrap();
+        assert!(file.ends_with("snapshot_2025-11-07.json"));
+        let snapshot = read_snapshot(&file).unwrap();
+        assert_eq!(snapshot.date, gen_snapshot(7).date);
+        assert_eq!(snapshot.members.len(), 3);
+        assert_eq!(snapshot.members[0].surname, "Testerin");
+        assert_eq!(snapshot.tick_offs[0].1, gen_toi_ok().to_vec());
+        assert_eq!(snapshot.findings, gen_snapshot(7).findings);
+    }
+
+    #[test]
+    fn test_diff_snapshots() {
+        let old = gen_snapshot(7);
+        let mut new = gen_snapshot(14);
+        new.members[1].location = Location::Leonberg;
+        new.findings = vec![gen_finding("New problem")];
+        let diff = diff_snapshots(&old, &new);
+        assert_eq!(diff.members.len(), 1);
+        assert_eq!(diff.new_findings, vec![gen_finding("New problem")]);
+        assert_eq!(diff.resolved_findings, vec![gen_finding("Old problem")]);
+
+        let mut reporter = MemoryReporter::default();
+        report_diff(&diff, &mut reporter);
+        assert_eq!(
+            reporter.section,
+            Some("Changes from 2025-11-07 to 2025-11-14".to_string())
+        );
+        assert_eq!(
+            reporter.messages,
+            vec![
+                "Member changes: 1",
+                "  Moved EV-2 Tester Bob from Perouse to Leonberg",
+                "New findings: 1",
+                "  Error: New problem",
+                "Resolved findings: 1",
+                "  Error: Old problem",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_latest() {
+        let dir = gen_dir("somato_test_snapshot_latest");
+        let mut reporter = MemoryReporter::default();
+        assert!(diff_latest(&dir, &mut reporter).is_err());
+        write_snapshot(&gen_snapshot(14), &dir).unwrap();
+        assert!(diff_latest(&dir, &mut reporter).is_err());
+        write_snapshot(&gen_snapshot(7), &dir).unwrap();
+        let mut latest = gen_snapshot(21);
+        latest.members.pop();
+        write_snapshot(&latest, &dir).unwrap();
+        assert_eq!(list_snapshots(&dir).unwrap().len(), 3);
+        let diff = diff_latest(&dir, &mut reporter).unwrap();
+        assert_eq!(
+            diff.old_date,
+            NaiveDate::from_ymd_opt(2025, 11, 14).unwrap()
+        );
+        assert_eq!(
+            diff.new_date,
+            NaiveDate::from_ymd_opt(2025, 11, 21).unwrap()
+        );
+        assert_eq!(diff.members.len(), 1);
+    }
+}
@@ -5,32 +5,82 @@ https://crates.io/crates/calamine
 https://docs.rs/calamine/latest/calamine/
 
 https://docs.rs/anyhow/latest/anyhow/
-https://docs.rs/injectorpp/latest/injectorpp/
 
 */
 
+pub use crate::annotate;
+pub use crate::capacity;
+pub use crate::html;
+pub use crate::input;
 pub use crate::joker;
 pub use crate::location::Location;
 pub use crate::member;
+pub use crate::packing;
+pub use crate::rebalance;
+pub use crate::redistribution;
+pub use crate::report;
+use crate::report::Reporter;
+pub use crate::roster;
+pub use crate::snapshot;
+pub use crate::source;
+pub use crate::statistics;
+pub use crate::substitution;
+pub use crate::summary;
 pub use crate::test_common;
 pub use crate::tickoff;
 use anyhow::Result;
 use anyhow::anyhow;
 use chrono::Datelike;
 use chrono::naive;
-use colored::Colorize;
 use serde::Deserialize;
 use std::env;
 use std::fs;
 use strum::IntoEnumIterator;
 
 /// Configuration to parse from JSON
-#[derive(Deserialize, PartialEq, Debug)]
+#[derive(Deserialize, PartialEq, Debug, Default)]
 pub struct Config {
     pub members: String,
     pub jokers: String,
     pub tickoff: String,
     pub date: String,
+    /// Format of the input files, guessed from the file extension if not set
+    pub format: Option<input::InputFormat>,
+    /// Optional CSV file for the packing list
+    pub packing_csv: Option<String>,
+    /// Optional xlsx file for the packing list
+    pub packing_xlsx: Option<String>,
+    /// Optional HTML file for the validation report
+    pub report_html: Option<String>,
+    /// Optional directory for copies of the input workbooks with the
+    /// problem cells highlighted
+    pub annotate_dir: Option<String>,
+    /// Optional file for the summary mail
+    pub summary_file: Option<String>,
+    /// Format of the summary, Markdown if not set
+    pub summary_format: Option<summary::SummaryFormat>,
+    /// Template file for the summary, a built in template if not set
+    pub summary_template: Option<String>,
+    /// Optional directory for the pickup rosters per location
+    pub roster_dir: Option<String>,
+    /// Format of the pickup rosters, HTML if not set
+    pub roster_format: Option<roster::RosterFormat>,
+    /// Optional directory to keep a snapshot of every run for the diff
+    pub snapshot_dir: Option<String>,
+    /// Additional texts of the status column and their status
+    pub status: Option<member::StatusMap>,
+    /// Share types with their columns, big and small if not set
+    pub share_types: Option<Vec<member::ShareType>>,
+    /// File with the co-holders of shared contracts, sheet "Teilernte"
+    pub co_holders: Option<String>,
+    /// File with the substitutions, sheet "Vertretung"
+    pub substitutions: Option<String>,
+    /// File with the redistributions of jokered shares, sheet "Umverteilung"
+    pub redistributions: Option<String>,
+    /// Capacity limits of the depots
+    pub capacity: Option<Vec<capacity::Capacity>>,
+    /// Distances between neighbouring depots for the rebalancing
+    pub distances: Option<Vec<rebalance::Distance>>,
 }
 
 /// Read and return the base config.
@@ -40,33 +90,204 @@ pub fn read_config(file: &str) -> Result<Config> {
     Ok(config)
 }
 
-/// Return the location of the configuration file.
-/// Separated into function with simpler interface.
-/// Was needed to mock it.
-pub fn get_config_file() -> Result<String> {
-    let arg = env::args().collect::<Vec<String>>();
-    let inputfile = if arg.len() == 1 {
-        "config_synth.toml".to_string()
-    } else if arg.len() == 2 {
-        arg.get(1).unwrap().to_owned()
-    } else {
-        return Err(anyhow!("Wrong amount of command line parameters"));
-    };
-    Ok(inputfile)
+/// What somato is asked to do
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Command {
+    /// Check the data of the configured date
+    #[default]
+    Check,
+    /// Show the changes between the two latest snapshots
+    Diff,
+    /// Show the changes between two member files
+    DiffMembers { old: String, new: String },
+    /// Propose member moves between neighbouring depots
+    Rebalance,
+    /// Show statistics over the jokers of the whole season
+    Stats,
+}
+
+/// Parsed command line arguments
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Args {
+    pub command: Command,
+    pub config_file: String,
+    /// Verbosity given with -v, -w or -q
+    pub level: Option<report::Level>,
+    /// Report the findings as JSON
+    pub json: bool,
+    /// Report into this file instead of the terminal
+    pub output: Option<String>,
+}
+
+/// Parse the command line arguments.
+/// Usage: somato [diff|rebalance|stats] [-v|-w|-q] [--json] [--output FILE]
+///               [CONFIG]
+///        somato diff-members [-v|-w|-q] [--json] [--output FILE] OLD NEW
+///               [CONFIG]
+pub fn parse_args(args: &[String]) -> Result<Args> {
+    let mut result = Args::default();
+    let mut config_file = None;
+    let mut diff_members = false;
+    let mut files = Vec::new();
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-v" | "--verbose" => result.level = Some(report::Level::Debug),
+            "-w" | "--warnings" => result.level = Some(report::Level::Warn),
+            "-q" | "--quiet" => result.level = Some(report::Level::Off),
+            "--json" => result.json = true,
+            "--output" => {
+                let file = iter
+                    .next()
+                    .ok_or(anyhow!("Missing file name after --output"))?;
+                result.output = Some(file.to_owned());
+            }
+            a if a.starts_with('-') => {
+                return Err(anyhow!("Unknown command line parameter {a}"));
+            }
+            "diff"
+                if config_file.is_none()
+                    && result.command == Command::Check =>
+            {
+                result.command = Command::Diff;
+            }
+            "rebalance"
+                if config_file.is_none()
+                    && result.command == Command::Check =>
+            {
+                result.command = Command::Rebalance;
+            }
+            "stats"
+                if config_file.is_none()
+                    && result.command == Command::Check =>
+            {
+                result.command = Command::Stats;
+            }
+            "diff-members"
+                if config_file.is_none()
+                    && result.command == Command::Check
+                    && !diff_members =>
+            {
+                diff_members = true;
+            }
+            a if diff_members => files.push(a.to_owned()),
+            a => {
+                if config_file.is_some() {
+                    return Err(anyhow!(
+                        "Wrong amount of command line parameters"
+                    ));
+                }
+                config_file = Some(a.to_owned());
+            }
+        }
+    }
+    if diff_members {
+        if files.len() == 3 {
+            config_file = files.pop();
+        }
+        let [old, new] = <[String; 2]>::try_from(files).map_err(|_| {
+            anyhow!("diff-members needs the old and the new member file")
+        })?;
+        result.command = Command::DiffMembers { old, new };
+    }
+    result.config_file =
+        config_file.unwrap_or_else(|| "config_synth.toml".to_string());
+    Ok(result)
+}
+
+/// Loads the configuration from a file name
+pub type ConfigLoader<'a> = Box<dyn Fn(&str) -> Result<Config> + 'a>;
+
+/// Creates the data source for a configuration
+pub type SourceLoader<'a> =
+    Box<dyn Fn(&Config) -> Box<dyn source::DataSource> + 'a>;
+
+/// Everything somato_main needs from the outside.
+/// Use Context::from_env for the real thing, tests can inject their own
+/// arguments, configuration, data and reporter.
+pub struct Context<'a> {
+    pub args: Vec<String>,
+    pub load_config: ConfigLoader<'a>,
+    pub load_source: SourceLoader<'a>,
+    pub reporter: &'a mut dyn Reporter,
+}
+
+impl<'a> Context<'a> {
+    /// Context with command line arguments, config and spreadsheet files
+    /// from disk and output to the given reporter.
+    pub fn from_env(reporter: &'a mut dyn Reporter) -> Self {
+        Context {
+            args: env::args().collect(),
+            load_config: Box::new(read_config),
+            load_source: Box::new(|config| {
+                Box::new(source::SpreadsheetSource::from_config(config))
+            }),
+            reporter,
+        }
+    }
 }
 
 /// Main entry point for somato.
 pub fn somato_main() -> Result<()> {
     let _ = enable_ansi_support::enable_ansi_support();
-    println!("{}", "*".repeat(80));
+    let args = parse_args(&env::args().collect::<Vec<String>>())?;
+    let level = report::Level::select(
+        args.level,
+        env::var("SOMATO_LOG").ok().as_deref(),
+    )?;
+    let mut reporter =
+        report::create_reporter(level, args.json, args.output.as_deref())?;
+    somato_main_with_context(&mut Context::from_env(reporter.as_mut()))
+}
 
-    let inputfile = get_config_file()?;
-    let config = read_config(&inputfile)?;
+/// Main flow of somato with everything taken from the context.
+pub fn somato_main_with_context(context: &mut Context) -> Result<()> {
+    context.reporter.message(&"*".repeat(80));
 
-    somato_runner(&config)?;
+    let args = parse_args(&context.args)?;
+    match &args.command {
+        Command::Check => {
+            let config = (context.load_config)(&args.config_file)?;
+            let source = (context.load_source)(&config);
+            somato_runner_with_source(
+                source.as_ref(),
+                &config,
+                context.reporter,
+            )?;
+        }
+        Command::Diff => {
+            let config = (context.load_config)(&args.config_file)?;
+            let dir = config
+                .snapshot_dir
+                .as_ref()
+                .ok_or(anyhow!("No snapshot_dir configured for the diff"))?;
+            snapshot::diff_latest(dir, context.reporter)?;
+        }
+        Command::DiffMembers { old, new } => {
+            let config = (context.load_config)(&args.config_file)?;
+            member::diff_member_files(
+                old,
+                new,
+                config.format,
+                &member::status_map(config.status.as_ref()),
+                &member::share_types(config.share_types.as_ref()),
+                context.reporter,
+            )?;
+        }
+        Command::Rebalance => {
+            let config = (context.load_config)(&args.config_file)?;
+            let source = (context.load_source)(&config);
+            rebalance_with_source(source.as_ref(), &config, context.reporter)?;
+        }
+        Command::Stats => {
+            let config = (context.load_config)(&args.config_file)?;
+            let source = (context.load_source)(&config);
+            statistics_with_source(source.as_ref(), &config, context.reporter)?;
+        }
+    }
 
-    println!("{}", "*".repeat(80));
-    Ok(())
+    context.reporter.message(&"*".repeat(80));
+    context.reporter.finish()
 }
 
 /// Parse date given in config file.
@@ -90,7 +311,6 @@ pub fn parse_date(date: &str) -> Result<naive::NaiveDate> {
 
     let date = chrono::naive::NaiveDate::from_ymd_opt(year, month, day)
         .ok_or(anyhow!("Cannot process date"))?;
-    println!("Parsed Date {:?} {}", date, date.weekday());
     if date.weekday() != chrono::Weekday::Fri {
         return Err(anyhow!("Distribution should be on Fridays"));
     }
@@ -98,229 +318,726 @@ pub fn parse_date(date: &str) -> Result<naive::NaiveDate> {
 }
 
 /// Analyses the current state of Jokers.
-/// Returns the amount of active collectors, collectors for big and small.
+/// Returns the amount of active collectors and the shares they collect, the
+/// same amounts the tickoff lists are checked against.
 pub fn analyze_jokers(
     active_members: &[member::Member],
     jokers: &[joker::Joker],
     date: &chrono::NaiveDate,
-) -> (usize, usize, usize) {
+    reporter: &mut dyn Reporter,
+) -> (usize, member::Shares) {
     let weekly_jokers = joker::filter_jokers_by_date(jokers, date);
-    println!("Weekly jokers {} at {}", weekly_jokers.len(), date);
+    reporter.message(&format!(
+        "Weekly jokers {} at {}",
+        weekly_jokers.len(),
+        date
+    ));
 
     for location in Location::iter() {
         let weekly_jokers_loc =
             joker::filter_jokers_by_location(weekly_jokers.clone(), &location);
 
-        println!(
+        reporter.debug(&format!(
             "    Weekly jokers {} at {} in {:?}",
             weekly_jokers_loc.len(),
             date,
             &location
-        );
+        ));
+    }
+    for m in active_members
+        .iter()
+        .filter(|m| member::has_joker(m, &weekly_jokers))
+    {
+        reporter.debug(&format!(
+            "  Joker set: {} {} from {:?}",
+            m.surname, m.forename, m.location
+        ));
     }
 
     let active_collectors =
         member::filter_jokers(active_members, &weekly_jokers);
-    let members_jokers_big = member::filter_members_by_big(&active_collectors);
-    let members_jokers_small =
-        member::filter_members_by_small(&active_collectors);
-    println!(
-        "Active collectors: all {}, big: {}, small: {}",
-        active_collectors.len(),
-        members_jokers_big.len(),
-        members_jokers_small.len()
-    );
-    (
-        active_collectors.len(),
-        members_jokers_big.len(),
-        members_jokers_small.len(),
-    )
+    let shares = member::count_shares(&active_collectors);
+    reporter.message(&format!(
+        "Active collectors: all {}, shares {shares}",
+        active_collectors.len()
+    ));
+    (active_collectors.len(), shares)
+}
+
+/// Result of the analysis of one location
+#[derive(Debug, Clone)]
+pub struct LocationSummary {
+    pub location: Location,
+    /// Active members of the location
+    pub members: member::MemberList,
+    /// Shares of all active members
+    pub shares: member::Shares,
+    /// Jokers set at the analysed date
+    pub jokers: joker::JokerList,
+    /// Shares of the members that collect at the analysed date
+    pub collected: member::Shares,
+    /// Collected plus received redistributed shares, the amounts the tickoff
+    /// list should show
+    pub expected: member::Shares,
+    /// Amounts listed in the tickoff list
+    pub tick_off: member::Shares,
+    /// Substitutes found in the tickoff list at the analysed date
+    pub substitutions: substitution::SubstitutionList,
+    /// What happened to the jokered shares at the analysed date
+    pub redistribution: redistribution::RedistributionSummary,
+}
+
+/// Everything somato_runner computes, e.g. to generate reports from
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    pub date: naive::NaiveDate,
+    pub locations: Vec<LocationSummary>,
+    pub total_shares: member::Shares,
+    pub warnings: usize,
+    pub findings: Vec<report::SectionFinding>,
 }
 
 /// Run analytics based on given configuration.
-pub fn somato_runner(config: &Config) -> Result<()> {
-    let members = member::read_members(&config.members)?;
-    let jokers = joker::read_jokers(&config.jokers)?;
+pub fn somato_runner(
+    config: &Config,
+    reporter: &mut dyn Reporter,
+) -> Result<Analysis> {
+    let source = source::SpreadsheetSource::from_config(config);
+    somato_runner_with_source(&source, config, reporter)
+}
+
+/// Run analytics on the data of the given source.
+/// Only the date and the output files are taken from the configuration.
+pub fn somato_runner_with_source(
+    source: &dyn source::DataSource,
+    config: &Config,
+    reporter: &mut dyn Reporter,
+) -> Result<Analysis> {
+    let mut recorder = report::Recorder::new(reporter);
+    let reporter: &mut dyn Reporter = &mut recorder;
+    let members = source.members()?;
+    let jokers = source.jokers()?;
     let mut warnings = 0;
 
-    println!("  Parsed {} members", members.len());
-    println!("  Parsed {} jokers", jokers.len());
+    reporter.debug(&format!("  Parsed {} members", members.len()));
+    reporter.debug(&format!("  Parsed {} jokers", jokers.len()));
 
-    let warnings_check = member::check_member_list(&members);
-    println!(
-        "{}",
-        format!("Detected {} warnings in member list", warnings_check)
-            .bright_red()
-    );
-    joker::check_joker_list(&members, &jokers)?;
+    let warnings_check = member::check_member_list(&members, reporter);
+    reporter.message(&format!(
+        "Detected {} warnings in member list",
+        warnings_check
+    ));
+    let share_types = member::share_types(config.share_types.as_ref());
+    joker::check_joker_list(&members, &jokers, &share_types, reporter)?;
 
-    let active_members = member::filter_active_members(members.clone());
     let date = parse_date(&config.date)?;
-    analyze_jokers(&active_members, &jokers, &date);
+    reporter.debug(&format!("Parsed Date {:?} {}", date, date.weekday()));
+    let mut active_members =
+        member::filter_active_members(members.clone(), &date);
+    reporter.debug(&format!("Found {} active members", active_members.len()));
+    for m in active_members.iter_mut() {
+        m.location = m.location_at(&date);
+    }
+    let capacities = config.capacity.clone().unwrap_or_default();
+    warnings +=
+        capacity::check_planned_moves(&members, &capacities, &date, reporter);
+    analyze_jokers(&active_members, &jokers, &date, reporter);
+    let packing = packing::packing(&active_members, &jokers, &date);
+    packing::print_packing(&packing, reporter);
+    if let Some(file) = &config.packing_csv {
+        packing::write_packing_csv(&packing, file)?;
+    }
+    if let Some(file) = &config.packing_xlsx {
+        packing::write_packing_xlsx(&packing, file)?;
+    }
+    if let Some(dir) = &config.roster_dir {
+        roster::write_rosters(
+            &active_members,
+            &jokers,
+            &date,
+            dir,
+            config.roster_format.unwrap_or_default(),
+        )?;
+    }
+    let weekly_jokers = joker::filter_jokers_by_date(&jokers, &date);
+    let weekly_substitutions = substitution::filter_substitutions_by_date(
+        &source.substitutions()?,
+        &date,
+    );
+    let weekly_redistributions = redistribution::filter_redistributions_by_date(
+        &source.redistributions()?,
+        &date,
+    );
 
-    // Iterate through locations
+    // Parse all tickoff lists first, the wrong location check needs them all.
+    // The sheets are checked later in the section of their location.
+    let mut sheets = Vec::new();
+    let mut tick_offs = Vec::new();
+    let mut substitutions = Vec::new();
     for location in Location::iter() {
-        println!("{}", "*".repeat(80));
-        println!("* Analysis for: {location:?}");
-        println!("{}", "*".repeat(80));
+        let sheet = source.tick_off(&location)?;
+        let tick_off = tickoff::deduplicate(&sheet.items);
+        let (tick_off, applied) = substitution::apply_substitutions(
+            &active_members,
+            &weekly_substitutions,
+            &tick_off,
+            reporter,
+        );
+        substitutions.push(applied);
+        tick_offs.push((location, tick_off));
+        sheets.push(sheet);
+    }
+    reporter.message(&format!(
+        "Substitutions: {}",
+        substitutions.iter().map(|s| s.len()).sum::<usize>()
+    ));
+    let wrong_locations =
+        tickoff::check_wrong_location(&active_members, &tick_offs, reporter);
+    warnings += wrong_locations.len();
+
+    let mut total_shares = member::Shares::default();
+    let mut summaries = Vec::new();
+
+    // Iterate through locations
+    for (((location, tick_off), substitutions), sheet) in
+        tick_offs.clone().into_iter().zip(substitutions).zip(sheets)
+    {
+        reporter.section(&section_title(&location));
+        warnings += tickoff::check_sums(&sheet, &share_types, reporter);
+        warnings +=
+            tickoff::check_duplicates(&sheet.items, &active_members, reporter);
         let loc =
             member::filter_members_by_location(&active_members, &location);
-        let mb = member::filter_members_by_big(&loc);
-        let ms = member::filter_members_by_small(&loc);
-        let all = loc.len();
-        let big = mb.len();
-        let small = ms.len();
-        println!("  Found {all}, big {big}, small {small}");
-        let diff: i32 = all as i32 - big as i32 - small as i32;
-
-        if diff != 0 {
-            println!(
-                "  {}",
-                format!(
-                    "Difference in member/portion amount ({}). \
-                    Proably one person has big and small amounts.",
-                    diff
-                )
-                .to_string()
-                .bright_red()
-            );
+        reporter.debug(&format!(
+            "  Found {} members in {:?}",
+            loc.len(),
+            location
+        ));
+        let shares = member::count_shares(&loc);
+        reporter.debug(&format!("  Shares {shares}"));
+        total_shares += &shares;
+        let mut summary = LocationSummary {
+            location: location.clone(),
+            members: loc.clone(),
+            shares,
+            jokers: joker::filter_jokers_by_location(
+                weekly_jokers.clone(),
+                &location,
+            ),
+            collected: member::Shares::default(),
+            expected: member::Shares::default(),
+            tick_off: tickoff::get_amounts(&tick_off),
+            substitutions,
+            redistribution: redistribution::redistribution_summary(
+                &weekly_jokers,
+                &weekly_redistributions,
+                &location,
+            ),
+        };
+        if !weekly_redistributions.is_empty() {
+            let r = &summary.redistribution;
+            reporter.message(&format!(
+                "  Redistribution: jokered {}, given {}, received {}, unused {}",
+                r.jokered,
+                r.given,
+                r.received,
+                r.unused()
+            ));
         }
-        // member::print_members(&mb);
-        // member::print_members(&ms);
+        warnings += redistribution::check_redistribution(
+            &summary.redistribution,
+            reporter,
+        );
 
-        let tick_off = tickoff::tick_off_list(&config.tickoff, &location)?;
-        let tick_off = tickoff::deduplicate(&tick_off);
-        if let Some(warn) =
-            tickoff::check_for_members_in_tickoff_list(&loc, &tick_off).unwrap()
+        // Members at the wrong location are already reported
+        let loc: member::MemberList = loc
+            .into_iter()
+            .filter(|m| {
+                !wrong_locations.iter().any(|w| {
+                    w.member.contract_no == m.contract_no
+                        && w.member.member_no == m.member_no
+                })
+            })
+            .collect();
+        let tick_off: tickoff::TickOffList = tick_off
+            .into_iter()
+            .filter(|t| {
+                !wrong_locations
+                    .iter()
+                    .any(|w| w.listed_at == location && w.name == t.name)
+            })
+            .collect();
+        let received: redistribution::RedistributionList =
+            weekly_redistributions
+                .iter()
+                .filter(|r| r.to == location)
+                .cloned()
+                .collect();
+        let (tick_off, recipients, recipient_warnings) =
+            redistribution::split_recipients(&tick_off, &received, reporter);
+        warnings += recipient_warnings;
+        if let Some(warn) = tickoff::check_for_members_in_tickoff_list(
+            &loc,
+            &tick_off,
+            &weekly_jokers,
+            reporter,
+        )
+        .unwrap()
         {
             warnings += warn;
         };
+        if let Some(warn) = tickoff::check_tickoff_list_against_members(
+            &loc, &tick_off, reporter,
+        )
+        .unwrap()
+        {
+            warnings += warn;
+        }
+        warnings += tickoff::check_schedules(
+            &loc,
+            &tick_off,
+            &summary.substitutions,
+            &date,
+            reporter,
+        );
+        let collectors = member::filter_jokers(&loc, &weekly_jokers);
+        let collected = member::count_shares(&collectors);
+        reporter.debug(&format!("  Collected shares {collected}"));
+        // The tickoff list also holds the redistributed shares
+        let mut expected = collected.clone();
+        expected += &summary.redistribution.received;
+        if let Some(c) = capacity::capacity_for(&capacities, &location) {
+            let at_depot =
+                member::filter_jokers(&summary.members, &weekly_jokers);
+            warnings += capacity::check_capacity(
+                c,
+                at_depot.len(),
+                &expected,
+                reporter,
+            );
+        }
+        let handed_out = [tick_off, recipients].concat();
         if let Some(warn) =
-            tickoff::check_tickoff_list_against_members(&loc, &tick_off)
+            tickoff::check_share_amounts(&expected, &handed_out, reporter)
                 .unwrap()
         {
             warnings += warn;
         }
+        summary.collected = collected;
+        summary.expected = expected;
+        summaries.push(summary);
     }
 
-    println!("Accumulated {warnings} warnings");
-    Ok(())
+    reporter.message(&format!("Shares over all locations {total_shares}"));
+    reporter.message(&format!("Accumulated {warnings} warnings"));
+    let analysis = Analysis {
+        date,
+        locations: summaries,
+        total_shares,
+        warnings,
+        findings: recorder.findings.clone(),
+    };
+    if let Some(file) = &config.report_html {
+        html::write_html(&analysis, file)?;
+    }
+    if let Some(dir) = &config.annotate_dir {
+        write_annotated_inputs(config, &analysis.findings, dir, &mut recorder)?;
+    }
+    if let Some(dir) = &config.snapshot_dir {
+        let snapshot = snapshot::Snapshot {
+            date,
+            members,
+            jokers: weekly_jokers,
+            tick_offs,
+            findings: analysis.findings.clone(),
+        };
+        snapshot::write_snapshot(&snapshot, dir)?;
+    }
+    if let Some(file) = &config.summary_file {
+        summary::write_summary(
+            &analysis,
+            config.summary_template.as_deref(),
+            config.summary_format.unwrap_or_default(),
+            file,
+        )?;
+    }
+    Ok(analysis)
+}
+
+/// Propose member moves that balance the active members of the configured
+/// date between neighbouring depots. Only reports, the data is not changed.
+pub fn rebalance_with_source(
+    source: &dyn source::DataSource,
+    config: &Config,
+    reporter: &mut dyn Reporter,
+) -> Result<Vec<rebalance::Proposal>> {
+    let date = parse_date(&config.date)?;
+    let mut active_members =
+        member::filter_active_members(source.members()?, &date);
+    for m in active_members.iter_mut() {
+        m.location = m.location_at(&date);
+    }
+    let counts = Location::iter()
+        .filter(|l| *l != Location::NotParsed)
+        .map(|l| {
+            let count =
+                member::filter_members_by_location(&active_members, &l).len();
+            format!("{l:?} {count}")
+        })
+        .collect::<Vec<String>>();
+    reporter
+        .message(&format!("Active members at {date}: {}", counts.join(", ")));
+    let proposals = rebalance::propose_moves(
+        &active_members,
+        config.capacity.as_deref().unwrap_or_default(),
+        config.distances.as_deref().unwrap_or_default(),
+    );
+    rebalance::report_proposals(&proposals, reporter);
+    Ok(proposals)
+}
+
+/// Collect and report the statistics over all jokers of the season.
+/// The distributions are on the weekday of the configured date.
+pub fn statistics_with_source(
+    source: &dyn source::DataSource,
+    config: &Config,
+    reporter: &mut dyn Reporter,
+) -> Result<statistics::Statistics> {
+    let weekday = naive::NaiveDate::parse_from_str(&config.date, "%Y-%m-%d")
+        .map_err(|e| {
+            anyhow!(format!("Error {e} while parsing date {}", config.date))
+        })?
+        .weekday();
+    let statistics = statistics::season_statistics(
+        &source.members()?,
+        &source.jokers()?,
+        weekday,
+    );
+    statistics::report_statistics(&statistics, reporter);
+    Ok(statistics)
+}
+
+/// Title of the report section of a location
+pub fn section_title(location: &Location) -> String {
+    format!("Analysis for: {location:?}")
+}
+
+/// Write copies of the input workbooks with the findings as notes into the
+/// given directory. CSV inputs cannot be annotated and are skipped with a
+/// warning. Inputs with the same file name in different directories get
+/// numbered copies.
+/// Returns the written files with the amount of annotated cells.
+pub fn write_annotated_inputs(
+    config: &Config,
+    findings: &[report::SectionFinding],
+    dir: &str,
+    reporter: &mut dyn Reporter,
+) -> Result<Vec<(String, usize)>> {
+    let inputs = [&config.members, &config.jokers, &config.tickoff];
+    let mut written: Vec<(String, usize)> = Vec::new();
+    for (i, input) in inputs.iter().enumerate() {
+        if inputs[..i].contains(input) {
+            continue;
+        }
+        let format = match config.format {
+            Some(f) => f,
+            None => input::InputFormat::from_file(input)?,
+        };
+        if format == input::InputFormat::Csv {
+            reporter.warning(format!("Cannot annotate CSV file {input}"));
+            continue;
+        }
+        let taken: Vec<String> =
+            written.iter().map(|(output, _)| output.clone()).collect();
+        let output = annotate::annotated_file_name(input, dir, &taken);
+        let annotated = annotate::write_annotated(input, findings, &output)?;
+        written.push((output, annotated));
+    }
+    Ok(written)
 }
 
 #[cfg(test)]
 mod test_somato {
     use super::*;
+    use crate::report::{MemoryReporter, QuietReporter, Severity};
     use crate::test_common::test_common::*;
-    use injectorpp::interface::injector::*;
 
-    // #[ignore] // Fails randomly, when run in multiple threads
+    fn gen_source() -> source::MemorySource {
+        source::MemorySource {
+            members: gen_members().to_vec(),
+            jokers: vec![gen_joker_a()],
+            tick_offs: vec![(Location::Perouse, gen_toi_ok().to_vec())],
+            ..Default::default()
+        }
+    }
+
     #[test]
-    pub fn test_somato_main() {
-        let mut injector = InjectorPP::new();
-        injector
-            .when_called(
-                injectorpp::func!(fn (somato_runner)(&Config) -> Result<()>),
-            )
-            .will_execute(injectorpp::fake!(
-                func_type: fn(_c:&Config) -> Result<()>,
-                returns: Ok(()),
-                times: 1
-            ));
-        injector
-            .when_called(
-                injectorpp::func!(fn (read_config)(&str) -> Result<Config>),
-            )
-            .will_execute(injectorpp::fake!(
-                func_type: fn(_c:&str) -> Result<Config>,
-            returns: Ok(Config{
-                members : "tests/test_data/members_synthetic.xlsx".to_string(),
-                jokers : "tests/test_data/jokers_synthetic.xlsx".to_string(),
-                tickoff : "tests/test_data/tickoff_synthetic.xlsx".to_string(),
-                date : "2025-12-19".to_string(),
-            }),
-                times: 1
-            ));
-        injector
-            .when_called(
-                injectorpp::func!(fn (get_config_file)() -> Result<String>),
+    fn test_somato_main_with_context() {
+        let mut reporter = MemoryReporter::default();
+        {
+            let mut context = Context {
+                args: vec!["somato".to_string(), "some_file".to_string()],
+                load_config: Box::new(|file| {
+                    assert_eq!(file, "some_file");
+                    Ok(Config {
+                        date: "2025-12-19".to_string(),
+                        ..Default::default()
+                    })
+                }),
+                load_source: Box::new(|_| Box::new(gen_source())),
+                reporter: &mut reporter,
+            };
+            let result = somato_main_with_context(&mut context);
+            assert!(result.is_ok());
+        }
+        assert_eq!(reporter.messages.first().unwrap(), &"*".repeat(80));
+        assert_eq!(reporter.messages.last().unwrap(), &"*".repeat(80));
+        assert_eq!(reporter.section, Some("Analysis for: NotParsed".into()));
+        assert_eq!(reporter.count(Severity::Error), 0);
+    }
+
+    #[test]
+    fn test_somato_main_with_context_rebalance() {
+        let mut reporter = MemoryReporter::default();
+        {
+            let mut context = Context {
+                args: vec!["somato".to_string(), "rebalance".to_string()],
+                load_config: Box::new(|_| {
+                    Ok(Config {
+                        date: "2025-12-19".to_string(),
+                        distances: Some(vec![rebalance::Distance {
+                            from: Location::Perouse,
+                            to: Location::Gerlingen,
+                            distance: 3,
+                        }]),
+                        ..Default::default()
+                    })
+                }),
+                load_source: Box::new(|_| Box::new(gen_source())),
+                reporter: &mut reporter,
+            };
+            assert!(somato_main_with_context(&mut context).is_ok());
+        }
+        assert!(
+            reporter.messages.contains(
+                &"Active members at 2025-12-19: Perouse 3, Gerlingen 0, \
+              Renningen 0, WeilDerStadt 0, Leonberg 0, Neuhausen 0"
+                    .to_string()
             )
-            .will_execute(injectorpp::fake!(
-                func_type: fn() -> Result<String>,
-                returns: Ok("some_file".to_string()),
-                times: 1
-            ));
-        let result = somato_main();
-        println!("{:?}", result);
-        assert!(result.is_ok());
+        );
+        assert!(reporter.messages.contains(&"Proposed moves: 1".to_string()));
+        assert_eq!(reporter.count(Severity::Warning), 0);
     }
 
-    #[ignore] // Fails randomly, when run in multiple threads
     #[test]
-    pub fn test_somato_main_2() {
-        let mut injector = InjectorPP::new();
-        injector
-            .when_called(
-                injectorpp::func!(fn (somato_runner)(&Config) -> Result<()>),
+    fn test_somato_main_with_context_stats() {
+        let mut reporter = MemoryReporter::default();
+        {
+            let mut context = Context {
+                args: vec!["somato".to_string(), "stats".to_string()],
+                load_config: Box::new(|_| {
+                    Ok(Config {
+                        date: "2025-11-07".to_string(),
+                        ..Default::default()
+                    })
+                }),
+                load_source: Box::new(|_| Box::new(gen_source())),
+                reporter: &mut reporter,
+            };
+            assert!(somato_main_with_context(&mut context).is_ok());
+        }
+        assert!(reporter.messages.contains(&"Peak weeks:".to_string()));
+        assert!(
+            reporter.messages.contains(
+                &"Season 0001-01-05 to 0001-01-05: 1 distributions, 1 jokers"
+                    .to_string()
             )
-            .will_execute(injectorpp::fake!(
-                func_type: fn(_c:&Config) -> Result<()>,
-                returns: Ok(()),
-                times: 1
-            ));
-        let result = somato_main();
-        println!("{:?}", result);
+        );
+    }
+
+    #[test]
+    fn test_somato_main_with_context_default_config() {
+        let mut context = Context {
+            args: vec!["somato".to_string()],
+            load_config: Box::new(read_config),
+            load_source: Box::new(|_| Box::new(gen_source())),
+            reporter: &mut QuietReporter::default(),
+        };
+        let result = somato_main_with_context(&mut context);
         assert!(result.is_ok());
     }
 
-    /// Issues
-    /// 1) the injector is not cleaned up correctly
-    /// 2) creating a second injector seems to hang forever
-    #[ignore] // Cannot work that way
     #[test]
-    pub fn test_somato_main_fake() {
-        let mut injector = InjectorPP::new();
-        injector
-            .when_called(
-                injectorpp::func!(fn (somato_runner)(&Config) -> Result<()>),
-            )
-            .will_execute(injectorpp::fake!(
-                func_type: fn(_c:&Config) -> Result<()>,
-                returns: Ok(()),
-                times: 1
-            ));
+    fn test_somato_main_with_context_errors() {
+        let mut context = Context {
+            args: vec!["somato".to_string(), "a".to_string(), "b".to_string()],
+            load_config: Box::new(|_| panic!("Must not be called")),
+            load_source: Box::new(|_| Box::new(gen_source())),
+            reporter: &mut QuietReporter::default(),
+        };
+        assert!(somato_main_with_context(&mut context).is_err());
 
-        let _config: Config = toml::from_str(
-            r#"
-        members = "tests/test_data/members_synthetic.xlsx"
-        jokers = "tests/test_data/jokers_synthetic.xlsx"
-        tickoff = "tests/test_data/tickoff_synthetic.xlsx"
-        date = "2025-12-19"
-        "#,
-        )
-        .unwrap();
+        let mut context = Context {
+            args: vec!["somato".to_string()],
+            load_config: Box::new(|_| Err(anyhow!("No config"))),
+            load_source: Box::new(|_| Box::new(gen_source())),
+            reporter: &mut QuietReporter::default(),
+        };
+        assert!(somato_main_with_context(&mut context).is_err());
 
-        // injector_2
-        injector
-            .when_called(
-                injectorpp::func!(fn (read_config)(&str) -> Result<Config>),
-            )
-            .will_execute(injectorpp::fake!(
-            func_type: fn(_a:&str) -> Result<Config>,
-            returns: Ok(Config{
-                members : "tests/test_data/members_synthetic.xlsx".to_string(),
-                jokers : "tests/test_data/jokers_synthetic.xlsx".to_string(),
-                tickoff : "tests/test_data/tickoff_synthetic.xlsx".to_string(),
-                date : "2025-12-19".to_string(),
-            }),
-            times: 1
-            ));
+        // Wrong date
+        let mut context = Context {
+            args: vec!["somato".to_string()],
+            load_config: Box::new(|_| Ok(Config::default())),
+            load_source: Box::new(|_| Box::new(gen_source())),
+            reporter: &mut QuietReporter::default(),
+        };
+        assert!(somato_main_with_context(&mut context).is_err());
+    }
 
-        let result = somato_main();
-        println!("{:?}", result);
-        assert!(result.is_ok());
-        // make sure the injector guard is not optimised away
-        // println!("{:?}", inj.type_id());
+    #[test]
+    fn test_somato_main_with_context_diff() {
+        let dir = std::env::temp_dir().join("somato_test_context_diff");
+        let _ = std::fs::remove_dir_all(&dir);
+        let dir = dir.to_str().unwrap().to_string();
+        let config = |date: &str| Config {
+            date: date.to_string(),
+            snapshot_dir: Some(dir.clone()),
+            ..Default::default()
+        };
+        for (date, members) in [("2025-11-07", 3), ("2025-11-14", 2)] {
+            let mut context = Context {
+                args: vec!["somato".to_string()],
+                load_config: Box::new(|_| Ok(config(date))),
+                load_source: Box::new(move |_| {
+                    let mut source = gen_source();
+                    source.members.truncate(members);
+                    Box::new(source)
+                }),
+                reporter: &mut QuietReporter::default(),
+            };
+            somato_main_with_context(&mut context).unwrap();
+        }
+        let mut reporter = MemoryReporter::default();
+        {
+            let mut context = Context {
+                args: vec!["somato".to_string(), "diff".to_string()],
+                load_config: Box::new(|_| Ok(config("2025-11-14"))),
+                load_source: Box::new(|_| panic!("Must not be called")),
+                reporter: &mut reporter,
+            };
+            somato_main_with_context(&mut context).unwrap();
+        }
+        assert_eq!(
+            reporter.section,
+            Some("Changes from 2025-11-07 to 2025-11-14".to_string())
+        );
+        assert!(reporter.messages.contains(
+            &"  Removed contract EV-3 Testeress Cloe at Perouse".to_string()
+        ));
+
+        let mut context = Context {
+            args: vec!["somato".to_string(), "diff".to_string()],
+            load_config: Box::new(|_| Ok(Config::default())),
+            load_source: Box::new(|_| panic!("Must not be called")),
+            reporter: &mut QuietReporter::default(),
+        };
+        assert!(somato_main_with_context(&mut context).is_err());
     }
+
+    #[test]
+    fn test_parse_args() {
+        let args = |a: &[&str]| -> Vec<String> {
+            a.iter().map(|s| s.to_string()).collect()
+        };
+        let parsed = parse_args(&args(&["somato"])).unwrap();
+        assert_eq!(parsed.config_file, "config_synth.toml");
+        assert_eq!(parsed.level, None);
+        assert!(!parsed.json);
+        assert_eq!(parsed.output, None);
+        assert_eq!(
+            parse_args(&args(&["somato", "my.toml"]))
+                .unwrap()
+                .config_file,
+            "my.toml"
+        );
+        let parsed = parse_args(&args(&[
+            "somato", "-v", "--json", "--output", "r.json", "my.toml", "-q",
+        ]))
+        .unwrap();
+        assert_eq!(
+            parsed,
+            Args {
+                command: Command::Check,
+                config_file: "my.toml".to_string(),
+                level: Some(report::Level::Off),
+                json: true,
+                output: Some("r.json".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&["somato", "-w"])).unwrap().level,
+            Some(report::Level::Warn)
+        );
+        let parsed = parse_args(&args(&["somato", "diff", "my.toml"])).unwrap();
+        assert_eq!(parsed.command, Command::Diff);
+        assert_eq!(parsed.config_file, "my.toml");
+        assert_eq!(
+            parse_args(&args(&["somato", "diff"])).unwrap().command,
+            Command::Diff
+        );
+        assert!(parse_args(&args(&["somato", "my.toml", "diff"])).is_err());
+        let parsed =
+            parse_args(&args(&["somato", "rebalance", "my.toml"])).unwrap();
+        assert_eq!(parsed.command, Command::Rebalance);
+        assert_eq!(parsed.config_file, "my.toml");
+        assert_eq!(
+            parse_args(&args(&["somato", "stats"])).unwrap().command,
+            Command::Stats
+        );
+        assert_eq!(
+            parse_args(&args(&["somato", "diff-members", "a.xlsx", "b.xlsx"]))
+                .unwrap()
+                .command,
+            Command::DiffMembers {
+                old: "a.xlsx".to_string(),
+                new: "b.xlsx".to_string()
+            }
+        );
+        let parsed = parse_args(&args(&[
+            "somato",
+            "diff-members",
+            "-v",
+            "--json",
+            "a.xlsx",
+            "b.xlsx",
+        ]))
+        .unwrap();
+        assert_eq!(
+            parsed.command,
+            Command::DiffMembers {
+                old: "a.xlsx".to_string(),
+                new: "b.xlsx".to_string()
+            }
+        );
+        assert_eq!(parsed.level, Some(report::Level::Debug));
+        assert!(parsed.json);
+        assert!(parse_args(&args(&["somato", "diff-members", "a"])).is_err());
+        assert!(parse_args(&args(&["somato", "diff-members"])).is_err());
+        let parsed =
+            parse_args(&args(&["somato", "diff-members", "a", "b", "c.toml"]))
+                .unwrap();
+        assert_eq!(parsed.config_file, "c.toml");
+        assert!(
+            parse_args(&args(&["somato", "diff-members", "a", "b", "c", "d"]))
+                .is_err()
+        );
+        assert!(parse_args(&args(&["somato", "a", "b"])).is_err());
+        assert!(parse_args(&args(&["somato", "--output"])).is_err());
+        assert!(parse_args(&args(&["somato", "--what"])).is_err());
+        assert!(parse_args(&args(&["somato", "-x"])).is_err());
+    }
+
     #[test]
     fn test_read_config() {
         let config_expect = Config {
@@ -328,23 +1045,229 @@ mod test_somato {
             jokers: "tests/test_data/jokers_synthetic.xlsx".to_string(),
             tickoff: "tests/test_data/tickoff_synthetic.xlsx".to_string(),
             date: "2025-11-07".to_string(),
+            ..Default::default()
         };
         let config =
             read_config("config_synth.toml").expect("Failed to parse config");
         assert_eq!(config, config_expect);
     }
     #[test]
+    fn test_config_status_share_types() {
+        let config: Config = toml::from_str(
+            "members = \"m\"\njokers = \"j\"\ntickoff = \"t\"\n\
+             date = \"2025-11-07\"\n[status]\nProbeabo = \"trial\"\n\
+             [[share_types]]\nname = \"bread\"\nmember_column = 21\n\
+             joker_column = 7\ntickoff_column = 9\n",
+        )
+        .unwrap();
+        let statuses = member::status_map(config.status.as_ref());
+        let types = member::share_types(config.share_types.as_ref());
+        assert_eq!(types.len(), 1);
+        assert_eq!(types[0].name, "bread");
+        assert_eq!(types[0].tickoff_column, 9);
+        assert_eq!(statuses["Probeabo"], member::MemberStatus::Trial);
+        assert_eq!(statuses["aktiv"], member::MemberStatus::Active);
+    }
+    #[test]
+    fn test_config_capacity() {
+        let config: Config = toml::from_str(
+            "members = \"m\"\njokers = \"j\"\ntickoff = \"t\"\n\
+             date = \"2025-11-07\"\n[[capacity]]\nlocation = \"Perouse\"\n\
+             collectors = 40\n[[capacity]]\nlocation = \"WeilDerStadt\"\n\
+             shares = 30\n",
+        )
+        .unwrap();
+        let capacities = config.capacity.unwrap();
+        assert_eq!(capacities.len(), 2);
+        assert_eq!(capacities[0].collectors, Some(40));
+        assert_eq!(capacities[0].shares, None);
+        assert_eq!(capacities[1].location, Location::WeilDerStadt);
+    }
+    #[test]
+    fn test_somato_runner_capacity() {
+        let config = Config {
+            date: "2025-11-07".to_string(),
+            capacity: Some(vec![capacity::Capacity {
+                location: Location::Perouse,
+                collectors: Some(2),
+                shares: Some(10),
+            }]),
+            ..Default::default()
+        };
+        let source = source::MemorySource {
+            members: gen_members().to_vec(),
+            tick_offs: vec![(Location::Perouse, gen_toi_ok().to_vec())],
+            ..Default::default()
+        };
+        let mut reporter = MemoryReporter::default();
+        let analysis =
+            somato_runner_with_source(&source, &config, &mut reporter).unwrap();
+        // Three collectors with 10 shares
+        assert_eq!(analysis.warnings, 1);
+        assert_eq!(
+            analysis.findings[0].finding.message,
+            "3 collectors exceed the capacity of 2 at Perouse"
+        );
+    }
+    #[test]
     fn test_somato_runner() {
         let config = Config {
             members: "tests/test_data/members_synthetic.xlsx".to_string(),
             jokers: "tests/test_data/jokers_synthetic.xlsx".to_string(),
             tickoff: "tests/test_data/tickoff_synthetic.xlsx".to_string(),
             date: "2025-11-07".to_string(),
+            ..Default::default()
         };
-        let result = somato_runner(&config);
+        let result = somato_runner(&config, &mut QuietReporter::default());
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_somato_runner_with_source() {
+        let config = Config {
+            date: "2025-11-07".to_string(),
+            ..Default::default()
+        };
+        let source = source::MemorySource {
+            members: gen_members().to_vec(),
+            jokers: vec![gen_joker_a()],
+            tick_offs: vec![(Location::Perouse, gen_toi_ok().to_vec())],
+            ..Default::default()
+        };
+        let mut reporter = MemoryReporter::default();
+        let analysis =
+            somato_runner_with_source(&source, &config, &mut reporter).unwrap();
+        assert_eq!(analysis.warnings, 0);
+        assert!(analysis.findings.is_empty());
+        let perouse = &analysis.locations[0];
+        assert_eq!(perouse.location, Location::Perouse);
+        assert_eq!(perouse.members.len(), 3);
+        assert_eq!(perouse.tick_off, perouse.collected);
+        assert_eq!(perouse.expected, perouse.collected);
+        assert_eq!(reporter.count(Severity::Error), 0);
+        assert!(
+            reporter
+                .messages
+                .contains(&"Accumulated 0 warnings".to_string())
+        );
+    }
+
+    #[test]
+    fn test_somato_runner_sheet_findings_in_section() {
+        let config = Config {
+            date: "2025-11-07".to_string(),
+            ..Default::default()
+        };
+        let mut tick_off = gen_toi_ok().to_vec();
+        tick_off.push(tick_off[1].clone());
+        let source = source::MemorySource {
+            members: gen_members().to_vec(),
+            jokers: vec![gen_joker_a()],
+            tick_offs: vec![(Location::Perouse, tick_off)],
+            ..Default::default()
+        };
+        let analysis = somato_runner_with_source(
+            &source,
+            &config,
+            &mut QuietReporter::default(),
+        )
+        .unwrap();
+        let duplicate = analysis
+            .findings
+            .iter()
+            .find(|f| f.finding.message.starts_with("Duplicated entry"))
+            .unwrap();
+        assert_eq!(
+            duplicate.section.as_deref(),
+            Some(section_title(&Location::Perouse).as_str())
+        );
+    }
+
+    #[test]
+    fn test_somato_runner_with_substitution() {
+        let date = naive::NaiveDate::from_ymd_opt(2025, 11, 7).unwrap();
+        let config = Config {
+            date: date.to_string(),
+            ..Default::default()
+        };
+        let [a, b, mut c] = gen_toi_ok();
+        c.name = "Nachbarin, N.".to_string();
+        let source = source::MemorySource {
+            members: gen_members().to_vec(),
+            tick_offs: vec![(Location::Perouse, vec![a, b, c])],
+            substitutions: vec![substitution::Substitution {
+                date,
+                surname: "Testeress".to_string(),
+                forename: "Cloe".to_string(),
+                substitute: "Nachbarin, N.".to_string(),
+                line: 2,
+            }],
+            ..Default::default()
+        };
+        let mut reporter = MemoryReporter::default();
+        let analysis =
+            somato_runner_with_source(&source, &config, &mut reporter).unwrap();
+        assert_eq!(analysis.warnings, 0);
+        assert_eq!(analysis.locations[0].substitutions.len(), 1);
+        assert!(reporter.messages.contains(&"Substitutions: 1".to_string()));
+    }
+
+    #[test]
+    fn test_somato_runner_with_redistribution() {
+        let date = naive::NaiveDate::from_ymd_opt(2025, 11, 7).unwrap();
+        let config = Config {
+            date: date.to_string(),
+            ..Default::default()
+        };
+        let joker = joker::Joker {
+            date,
+            ..gen_joker_a()
+        };
+        let [_a, b, c] = gen_toi_ok();
+        let recipient = tickoff::TickOffItem {
+            name: "Tafel, T.".to_string(),
+            shares: member::Shares::from([(member::BIG, 2)]),
+            source: None,
+        };
+        let mut source = source::MemorySource {
+            members: gen_members().to_vec(),
+            jokers: vec![joker],
+            tick_offs: vec![
+                (Location::Perouse, vec![b, c]),
+                (Location::Gerlingen, vec![recipient]),
+            ],
+            redistributions: vec![redistribution::Redistribution {
+                date,
+                from: Location::Perouse,
+                to: Location::Gerlingen,
+                recipient: "Tafel, T.".to_string(),
+                shares: member::Shares::from([(member::BIG, 2)]),
+                line: 2,
+            }],
+            ..Default::default()
+        };
+        let analysis = somato_runner_with_source(
+            &source,
+            &config,
+            &mut QuietReporter::default(),
+        )
+        .unwrap();
+        assert_eq!(analysis.warnings, 0);
+        let perouse = &analysis.locations[0].redistribution;
+        assert_eq!(perouse.given, member::Shares::from([(member::BIG, 2)]));
+        assert!(perouse.unused().is_empty());
+
+        // The recipient is missing, so the amounts do not match either
+        source.tick_offs.pop();
+        let analysis = somato_runner_with_source(
+            &source,
+            &config,
+            &mut QuietReporter::default(),
+        )
+        .unwrap();
+        assert_eq!(analysis.warnings, 2);
+    }
+
     #[test]
     fn test_parse_date() {
         assert_eq!(
@@ -378,9 +1301,18 @@ mod test_somato {
         let date = naive::NaiveDate::from_ymd_opt(2025, 11, 7).unwrap();
         let members = gen_members();
         let jokers = vec![gen_joker_b()];
-        let (m, b, s) = analyze_jokers(&members, &jokers, &date);
+        let mut reporter = MemoryReporter::default();
+        let (m, shares) =
+            analyze_jokers(&members, &jokers, &date, &mut reporter);
+        assert!(reporter.messages[0].starts_with("Weekly jokers 0"));
         assert_eq!(m, 3);
-        assert_eq!(b, 2);
-        assert_eq!(s, 2);
+        // Amounts, not heads: Cloe holds three shares of both types
+        assert_eq!(
+            shares,
+            member::Shares::from([(member::BIG, 5), (member::SMALL, 5)])
+        );
+        assert!(reporter.messages.contains(
+            &"Active collectors: all 3, shares big 5, small 5".to_string()
+        ));
     }
 }
@@ -0,0 +1,247 @@
+use crate::input::InputFormat;
+use crate::joker;
+use crate::location::Location;
+use crate::member;
+use crate::redistribution;
+use crate::somato::Config;
+use crate::substitution;
+use crate::tickoff;
+use anyhow::Result;
+
+/// Provides the member list
+pub trait MemberSource {
+    fn members(&self) -> Result<member::MemberList>;
+}
+
+/// Provides the joker list
+pub trait JokerSource {
+    fn jokers(&self) -> Result<joker::JokerList>;
+}
+
+/// Provides the substitutions
+pub trait SubstitutionSource {
+    fn substitutions(&self) -> Result<substitution::SubstitutionList>;
+}
+
+/// Provides the redistributions of jokered shares
+pub trait RedistributionSource {
+    fn redistributions(&self) -> Result<redistribution::RedistributionList>;
+}
+
+/// Provides the tickoff sheet of a location
+pub trait TickOffSource {
+    fn tick_off(&self, location: &Location) -> Result<tickoff::TickOffSheet>;
+}
+
+/// Provides all data that is needed to run somato
+pub trait DataSource:
+    MemberSource
+    + JokerSource
+    + SubstitutionSource
+    + RedistributionSource
+    + TickOffSource
+{
+}
+
+impl<
+    T: MemberSource
+        + JokerSource
+        + SubstitutionSource
+        + RedistributionSource
+        + TickOffSource,
+> DataSource for T
+{
+}
+
+/// Reads the data from spreadsheet files
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpreadsheetSource {
+    pub members: String,
+    pub jokers: String,
+    pub tickoff: String,
+    pub format: Option<InputFormat>,
+    pub statuses: member::StatusMap,
+    pub share_types: Vec<member::ShareType>,
+    /// Optional file with the co-holders of shared contracts
+    pub co_holders: Option<String>,
+    /// Optional file with the substitutions
+    pub substitutions: Option<String>,
+    /// Optional file with the redistributions
+    pub redistributions: Option<String>,
+}
+
+impl SpreadsheetSource {
+    /// Take the file names and format from the config
+    pub fn from_config(config: &Config) -> Self {
+        SpreadsheetSource {
+            members: config.members.clone(),
+            jokers: config.jokers.clone(),
+            tickoff: config.tickoff.clone(),
+            format: config.format,
+            statuses: member::status_map(config.status.as_ref()),
+            share_types: member::share_types(config.share_types.as_ref()),
+            co_holders: config.co_holders.clone(),
+            substitutions: config.substitutions.clone(),
+            redistributions: config.redistributions.clone(),
+        }
+    }
+}
+
+impl MemberSource for SpreadsheetSource {
+    fn members(&self) -> Result<member::MemberList> {
+        let mut members = member::read_members(
+            &self.members,
+            self.format,
+            &self.statuses,
+            &self.share_types,
+        )?;
+        if let Some(file) = &self.co_holders {
+            member::read_co_holders(file, self.format, &mut members)?;
+        }
+        Ok(members)
+    }
+}
+
+impl JokerSource for SpreadsheetSource {
+    fn jokers(&self) -> Result<joker::JokerList> {
+        joker::read_jokers(&self.jokers, self.format, &self.share_types)
+    }
+}
+
+impl SubstitutionSource for SpreadsheetSource {
+    fn substitutions(&self) -> Result<substitution::SubstitutionList> {
+        match &self.substitutions {
+            Some(file) => substitution::read_substitutions(file, self.format),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+impl RedistributionSource for SpreadsheetSource {
+    fn redistributions(&self) -> Result<redistribution::RedistributionList> {
+        match &self.redistributions {
+            Some(file) => redistribution::read_redistributions(
+                file,
+                self.format,
+                &self.share_types,
+            ),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+impl TickOffSource for SpreadsheetSource {
+    fn tick_off(&self, location: &Location) -> Result<tickoff::TickOffSheet> {
+        tickoff::tick_off_sheet(
+            &self.tickoff,
+            location,
+            self.format,
+            &self.share_types,
+        )
+    }
+}
+
+/// Keeps the data in memory.
+/// Locations without tickoff list get an empty list, the sums of a sheet
+/// are those of its entries.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySource {
+    pub members: member::MemberList,
+    pub jokers: joker::JokerList,
+    pub substitutions: substitution::SubstitutionList,
+    pub redistributions: redistribution::RedistributionList,
+    pub tick_offs: Vec<(Location, tickoff::TickOffList)>,
+}
+
+impl MemberSource for MemorySource {
+    fn members(&self) -> Result<member::MemberList> {
+        Ok(self.members.clone())
+    }
+}
+
+impl JokerSource for MemorySource {
+    fn jokers(&self) -> Result<joker::JokerList> {
+        Ok(self.jokers.clone())
+    }
+}
+
+impl SubstitutionSource for MemorySource {
+    fn substitutions(&self) -> Result<substitution::SubstitutionList> {
+        Ok(self.substitutions.clone())
+    }
+}
+
+impl RedistributionSource for MemorySource {
+    fn redistributions(&self) -> Result<redistribution::RedistributionList> {
+        Ok(self.redistributions.clone())
+    }
+}
+
+impl TickOffSource for MemorySource {
+    fn tick_off(&self, location: &Location) -> Result<tickoff::TickOffSheet> {
+        let tick_off = self
+            .tick_offs
+            .iter()
+            .find(|(l, _)| l == location)
+            .map(|(_, t)| t.clone())
+            .unwrap_or_default();
+        Ok(tickoff::TickOffSheet {
+            sums: tickoff::get_amounts(&tick_off),
+            items: tick_off,
+        })
+    }
+}
+
+#[cfg(test)]
+mod source_tests {
+
+    use super::*;
+    use crate::test_common::test_common::*;
+
+    #[test]
+    fn test_spreadsheet_source() {
+        let config = Config {
+            members: "tests/test_data/members_synthetic.xlsx".to_string(),
+            jokers: "tests/test_data/jokers_synthetic.xlsx".to_string(),
+            tickoff: "tests/test_data/tickoff_synthetic.xlsx".to_string(),
+            date: "2025-12-19".to_string(),
+            ..Default::default()
+        };
+        let source = SpreadsheetSource::from_config(&config);
+        assert_eq!(source.members().unwrap().len(), 93);
+        assert_eq!(source.jokers().unwrap().len(), 15);
+        assert_eq!(
+            source.tick_off(&Location::Perouse).unwrap().items.len(),
+            12
+        );
+        assert!(
+            source
+                .tick_off(&Location::NotParsed)
+                .unwrap()
+                .items
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_memory_source() {
+        let source = MemorySource {
+            members: gen_members().to_vec(),
+            jokers: vec![gen_joker_a()],
+            tick_offs: vec![(Location::Perouse, gen_toi_ok().to_vec())],
+            ..Default::default()
+        };
+        assert_eq!(source.members().unwrap().len(), 3);
+        assert!(source.substitutions().unwrap().is_empty());
+        assert!(source.redistributions().unwrap().is_empty());
+        assert_eq!(source.jokers().unwrap().len(), 1);
+        assert_eq!(source.tick_off(&Location::Perouse).unwrap().items.len(), 3);
+        assert!(
+            source
+                .tick_off(&Location::Gerlingen)
+                .unwrap()
+                .items
+                .is_empty()
+        );
+    }
+}
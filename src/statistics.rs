@@ -0,0 +1,302 @@
+use crate::joker::{self, Joker};
+use crate::location::Location;
+use crate::member::{self, Member};
+use crate::report::Reporter;
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::collections::BTreeMap;
+use strum::IntoEnumIterator;
+
+/// Amount of peak weeks that are reported
+pub const PEAK_WEEKS: usize = 5;
+/// Amount of members with most jokers that are reported
+pub const TOP_MEMBERS: usize = 10;
+
+/// Jokers and pickups of one distribution
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeekStatistics {
+    pub date: NaiveDate,
+    /// Jokers per location
+    pub jokers: Vec<(Location, usize)>,
+    /// Members under contract at the date
+    pub active: usize,
+    /// Active members without joker
+    pub collectors: usize,
+}
+
+impl WeekStatistics {
+    /// Jokers over all locations
+    pub fn total(&self) -> usize {
+        self.jokers.iter().map(|(_, j)| j).sum()
+    }
+
+    /// Share of the active members that collect
+    pub fn pickup_rate(&self) -> Option<f64> {
+        (self.active > 0).then(|| self.collectors as f64 / self.active as f64)
+    }
+}
+
+/// Statistics over the whole season of the joker list
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Statistics {
+    /// Every distribution from the first to the last joker
+    pub weeks: Vec<WeekStatistics>,
+    /// Distributions with most jokers, e.g. the holiday weeks
+    pub peak_weeks: Vec<(NaiveDate, usize)>,
+    /// Contracts with most jokers, "Contract Surname Forename"
+    pub top_members: Vec<(String, usize)>,
+    /// Average of the weekly pickup rates
+    pub pickup_rate: Option<f64>,
+}
+
+/// Distribution weekday of the week of the date, jokers entered for another
+/// day count for the distribution of their week
+pub fn distribution_day(date: &NaiveDate, weekday: Weekday) -> NaiveDate {
+    let week = date.iso_week();
+    NaiveDate::from_isoywd_opt(week.year(), week.week(), weekday)
+        .unwrap_or(*date)
+}
+
+/// Collect the statistics over all distributions on the weekday from the
+/// first to the last joker
+pub fn season_statistics(
+    members: &[Member],
+    jokers: &[Joker],
+    weekday: Weekday,
+) -> Statistics {
+    let jokers: Vec<Joker> = jokers
+        .iter()
+        .map(|j| Joker {
+            date: distribution_day(&j.date, weekday),
+            ..j.clone()
+        })
+        .collect();
+    let (Some(first), Some(last)) = (
+        jokers.iter().map(|j| j.date).min(),
+        jokers.iter().map(|j| j.date).max(),
+    ) else {
+        return Statistics::default();
+    };
+    let mut weeks = Vec::new();
+    let mut date = first;
+    while date <= last {
+        let weekly = joker::filter_jokers_by_date(&jokers, &date);
+        let active = member::filter_active_members(members.to_vec(), &date);
+        let collectors = member::filter_jokers(&active, &weekly);
+        weeks.push(WeekStatistics {
+            date,
+            jokers: Location::iter()
+                .filter(|l| *l != Location::NotParsed)
+                .map(|l| {
+                    let count =
+                        weekly.iter().filter(|j| j.location == l).count();
+                    (l, count)
+                })
+                .collect(),
+            active: active.len(),
+            collectors: collectors.len(),
+        });
+        date += chrono::Duration::weeks(1);
+    }
+
+    let mut peak_weeks: Vec<(NaiveDate, usize)> =
+        weeks.iter().map(|w| (w.date, w.total())).collect();
+    peak_weeks.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    peak_weeks.truncate(PEAK_WEEKS);
+
+    // Jokers of co-holders count for the shared contract, jokers of unknown
+    // or inactive names are not counted
+    let mut per_contract: BTreeMap<&str, (String, usize)> = BTreeMap::new();
+    for j in jokers.iter() {
+        let Some(m) = members.iter().find(|m| {
+            m.is_active_at(&j.date) && m.is_holder(&j.surname, &j.forename)
+        }) else {
+            continue;
+        };
+        per_contract
+            .entry(&m.contract_no)
+            .or_insert_with(|| {
+                (format!("{} {} {}", m.contract_no, m.surname, m.forename), 0)
+            })
+            .1 += 1;
+    }
+    let mut top_members: Vec<(String, usize)> =
+        per_contract.into_values().collect();
+    top_members.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    top_members.truncate(TOP_MEMBERS);
+
+    let rates: Vec<f64> =
+        weeks.iter().filter_map(|w| w.pickup_rate()).collect();
+    let pickup_rate = (!rates.is_empty())
+        .then(|| rates.iter().sum::<f64>() / rates.len() as f64);
+
+    Statistics {
+        weeks,
+        peak_weeks,
+        top_members,
+        pickup_rate,
+    }
+}
+
+/// Report the season statistics
+pub fn report_statistics(statistics: &Statistics, reporter: &mut dyn Reporter) {
+    let (Some(first), Some(last)) =
+        (statistics.weeks.first(), statistics.weeks.last())
+    else {
+        reporter.message("No jokers in the season");
+        return;
+    };
+    reporter.message(&format!(
+        "Season {} to {}: {} distributions, {} jokers",
+        first.date,
+        last.date,
+        statistics.weeks.len(),
+        statistics.weeks.iter().map(|w| w.total()).sum::<usize>()
+    ));
+    reporter.message("Jokers per week:");
+    for w in statistics.weeks.iter() {
+        let locations = w
+            .jokers
+            .iter()
+            .map(|(l, j)| format!("{l:?} {j}"))
+            .collect::<Vec<String>>()
+            .join(", ");
+        reporter.message(&format!(
+            "  {}: all {}, {locations}",
+            w.date,
+            w.total()
+        ));
+    }
+    reporter.message("Peak weeks:");
+    for (date, jokers) in statistics.peak_weeks.iter() {
+        reporter.message(&format!("  {date}: {jokers} jokers"));
+    }
+    reporter.message("Members with most jokers:");
+    for (name, jokers) in statistics.top_members.iter() {
+        reporter.message(&format!("  {name}: {jokers}"));
+    }
+    if let Some(rate) = statistics.pickup_rate {
+        reporter.message(&format!(
+            "Average weekly pickup rate: {:.1}%",
+            rate * 100.0
+        ));
+    }
+}
+
+#[cfg(test)]
+mod statistics_tests {
+
+    use super::*;
+    use crate::member::{CoHolder, Schedule};
+    use crate::report::MemoryReporter;
+    use crate::test_common::test_common::*;
+    use chrono::Duration;
+
+    fn gen_joker(joker: Joker, date: NaiveDate) -> Joker {
+        Joker { date, ..joker }
+    }
+
+    fn gen_named_joker(
+        surname: &str,
+        forename: &str,
+        date: NaiveDate,
+    ) -> Joker {
+        Joker {
+            surname: surname.to_string(),
+            forename: forename.to_string(),
+            ..gen_joker(gen_joker_c(), date)
+        }
+    }
+
+    #[test]
+    fn test_distribution_day() {
+        let friday = NaiveDate::from_ymd_opt(2025, 11, 7).unwrap();
+        assert_eq!(distribution_day(&friday, Weekday::Fri), friday);
+        let monday = NaiveDate::from_ymd_opt(2025, 11, 3).unwrap();
+        assert_eq!(distribution_day(&monday, Weekday::Fri), friday);
+        let sunday = NaiveDate::from_ymd_opt(2025, 11, 9).unwrap();
+        assert_eq!(distribution_day(&sunday, Weekday::Fri), friday);
+        let wednesday = NaiveDate::from_ymd_opt(2025, 11, 5).unwrap();
+        assert_eq!(distribution_day(&friday, Weekday::Wed), wednesday);
+    }
+
+    #[test]
+    fn test_season_statistics() {
+        let date = |d| NaiveDate::from_ymd_opt(2025, 11, d).unwrap();
+        let jokers = [
+            gen_joker(gen_joker_a(), date(7)),
+            gen_joker(gen_joker_b(), date(7)),
+            gen_joker(gen_joker_a(), date(19)),
+            gen_joker(gen_joker_c(), date(28)),
+        ];
+        let statistics =
+            season_statistics(&gen_members(), &jokers, Weekday::Fri);
+        assert_eq!(statistics.weeks.len(), 4);
+        assert_eq!(statistics.weeks[0].total(), 2);
+        assert_eq!(statistics.weeks[0].jokers[0], (Location::Perouse, 2));
+        assert_eq!(statistics.weeks[1].total(), 0);
+        assert_eq!(statistics.weeks[2].date, date(21));
+        assert_eq!(statistics.weeks[2].collectors, 2);
+        assert_eq!(statistics.peak_weeks[0], (date(7), 2));
+        assert_eq!(statistics.peak_weeks.len(), 4);
+        assert_eq!(
+            statistics.top_members[0],
+            ("EV-1 Testerin Alice".to_string(), 2)
+        );
+        // (1/3 + 3/3 + 2/3 + 2/3) / 4 weeks
+        let rate = statistics.pickup_rate.unwrap();
+        assert!((rate - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_season_statistics_contracts() {
+        let date = NaiveDate::from_ymd_opt(2025, 11, 7).unwrap();
+        let [mut a, b, c] = gen_members();
+        a.co_holders.push(CoHolder {
+            surname: "Partnerin".to_string(),
+            forename: "Paula".to_string(),
+            schedule: Schedule::Always,
+        });
+        let jokers = [
+            gen_joker(gen_joker_b(), date),
+            gen_named_joker("Partnerin", "Paula", date),
+            gen_named_joker("Testerin", "Alice", date + Duration::weeks(1)),
+            gen_named_joker("Unbekannt", "Uwe", date),
+        ];
+        let statistics = season_statistics(&[a, b, c], &jokers, Weekday::Fri);
+        assert_eq!(
+            statistics.top_members,
+            vec![
+                ("EV-1 Testerin Alice".to_string(), 2),
+                ("EV-2 Tester Bob".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_report_statistics() {
+        let mut reporter = MemoryReporter::default();
+        report_statistics(&Statistics::default(), &mut reporter);
+        assert_eq!(reporter.messages, vec!["No jokers in the season"]);
+
+        let date = NaiveDate::from_ymd_opt(2025, 11, 7).unwrap();
+        let jokers = [gen_joker(gen_joker_a(), date)];
+        let statistics =
+            season_statistics(&gen_members(), &jokers, Weekday::Fri);
+        let mut reporter = MemoryReporter::default();
+        report_statistics(&statistics, &mut reporter);
+        assert_eq!(
+            reporter.messages,
+            vec![
+                "Season 2025-11-07 to 2025-11-07: 1 distributions, 1 jokers",
+                "Jokers per week:",
+                "  2025-11-07: all 1, Perouse 1, Gerlingen 0, Renningen 0, \
+                 WeilDerStadt 0, Leonberg 0, Neuhausen 0",
+                "Peak weeks:",
+                "  2025-11-07: 1 jokers",
+                "Members with most jokers:",
+                "  EV-1 Testerin Alice: 1",
+                "Average weekly pickup rate: 66.7%",
+            ]
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use crate::input;
+use crate::member::{self, Member};
+use crate::report::{Reporter, SourceRef};
+use crate::tickoff::TickOffItem;
+use anyhow::{Result, anyhow};
+use calamine::DataType;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Name of the sheet with the substitutions
+pub const SUBSTITUTION_SHEET: &str = "Vertretung";
+
+/// A member hands the share of one date to a substitute, who is listed in
+/// the tickoff list instead of the member
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Substitution {
+    pub date: NaiveDate,
+    pub surname: String,
+    pub forename: String,
+    /// Name of the substitute as written in the tickoff list, "Surname, N."
+    pub substitute: String,
+    pub line: u32,
+}
+
+pub type SubstitutionList = Vec<Substitution>;
+
+impl Substitution {
+    /// Cell of this substitution in the substitution sheet
+    pub fn source(&self, column: Option<u32>) -> SourceRef {
+        SourceRef::new(SUBSTITUTION_SHEET, self.line, column)
+    }
+}
+
+impl fmt::Display for Substitution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} for {} {}",
+            self.substitute, self.forename, self.surname
+        )
+    }
+}
+
+/// Read the substitutions.
+/// Columns: date, member surname, member forename, substitute name.
+pub fn read_substitutions(
+    substitution_file: &str,
+    format: Option<input::InputFormat>,
+) -> Result<SubstitutionList> {
+    let mut substitutions = Vec::new();
+    let Some(r) =
+        input::read_sheet(substitution_file, SUBSTITUTION_SHEET, format)?
+    else {
+        return Ok(substitutions);
+    };
+    for (line, row) in (2..).zip(r.rows().skip(1)) {
+        if (0..4).any(|x| row.get(x).is_none_or(|c| c.is_empty())) {
+            continue;
+        }
+        let text = |x: usize| {
+            row[x].as_string().unwrap_or_default().trim().to_string()
+        };
+        let date = member::parse_date_cell(&row[0], line)?.ok_or(anyhow!(
+            format!("Missing date in substitution line {line}")
+        ))?;
+        substitutions.push(Substitution {
+            date,
+            surname: text(1),
+            forename: text(2),
+            substitute: text(3),
+            line,
+        });
+    }
+    Ok(substitutions)
+}
+
+/// Return the substitutions of the given date
+pub fn filter_substitutions_by_date(
+    substitutions: &[Substitution],
+    date: &NaiveDate,
+) -> SubstitutionList {
+    substitutions
+        .iter()
+        .filter(|s| s.date == *date)
+        .cloned()
+        .collect()
+}
+
+/// Replace the names of substitutes in the tickoff list by the names of the
+/// members they collect for, so the checks find the members.
+/// Returns the list and the applied substitutions.
+pub fn apply_substitutions(
+    members: &[Member],
+    substitutions: &[Substitution],
+    tick_off: &[TickOffItem],
+    reporter: &mut dyn Reporter,
+) -> (Vec<TickOffItem>, SubstitutionList) {
+    let mut applied = Vec::new();
+    let mut tick_off = tick_off.to_vec();
+    for s in substitutions.iter() {
+        let Some(tick) = tick_off.iter_mut().find(|t| {
+            t.name.trim().to_lowercase() == s.substitute.to_lowercase()
+        }) else {
+            continue;
+        };
+        let Some(member) = members
+            .iter()
+            .find(|m| m.is_holder(&s.surname, &s.forename))
+        else {
+            reporter.warning_at(
+                format!(
+                    "Cannot find member {} {} of substitution line {}",
+                    s.surname, s.forename, s.line
+                ),
+                Some(s.source(Some(1))),
+            );
+            continue;
+        };
+        let initial = member.forename.chars().next().unwrap_or('?');
+        reporter.debug(&format!("  Substitute {s}"));
+        tick.name = format!("{}, {initial}.", member.surname);
+        applied.push(s.clone());
+    }
+    (tick_off, applied)
+}
+
+#[cfg(test)]
+mod substitution_tests {
+
+    use super::*;
+    use crate::member::{BIG, SMALL, Shares};
+    use crate::report::{MemoryReporter, Severity};
+    use crate::test_common::test_common::*;
+
+    fn gen_substitution(surname: &str, forename: &str) -> Substitution {
+        Substitution {
+            date: NaiveDate::from_ymd_opt(1, 1, 1).unwrap(),
+            surname: surname.to_string(),
+            forename: forename.to_string(),
+            substitute: "Nachbarin, N.".to_string(),
+            line: 2,
+        }
+    }
+
+    #[test]
+    fn test_filter_substitutions_by_date() {
+        let date = NaiveDate::from_ymd_opt(1, 1, 1).unwrap();
+        let mut later = gen_substitution("Tester", "Bob");
+        later.date = NaiveDate::from_ymd_opt(1, 1, 8).unwrap();
+        let list = vec![gen_substitution("Testerin", "Alice"), later];
+        let list = filter_substitutions_by_date(&list, &date);
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].surname, "Testerin");
+    }
+
+    #[test]
+    fn test_apply_substitutions() {
+        let [a, b, _c] = gen_toi_ok();
+        let neighbour = TickOffItem {
+            name: "Nachbarin, N.".to_string(),
+            shares: Shares::from([(BIG, 3), (SMALL, 3)]),
+            source: None,
+        };
+        let tick_off = vec![a.clone(), b, neighbour];
+        let mut reporter = MemoryReporter::default();
+        let (list, applied) = apply_substitutions(
+            &gen_members(),
+            &[gen_substitution("Testeress", "Cloe")],
+            &tick_off,
+            &mut reporter,
+        );
+        assert_eq!(applied.len(), 1);
+        assert_eq!(list[2].name, "Testeress, C.");
+        assert_eq!(list[0], a);
+        assert_eq!(reporter.count(Severity::Warning), 0);
+
+        // Substitute not listed
+        let (list, applied) = apply_substitutions(
+            &gen_members(),
+            &[gen_substitution("Testeress", "Cloe")],
+            &tick_off[..1],
+            &mut reporter,
+        );
+        assert!(applied.is_empty());
+        assert_eq!(list, vec![a]);
+
+        // Unknown member
+        let (_, applied) = apply_substitutions(
+            &gen_members(),
+            &[gen_substitution("Unbekannt", "Uwe")],
+            &tick_off,
+            &mut reporter,
+        );
+        assert!(applied.is_empty());
+        assert_eq!(reporter.count(Severity::Warning), 1);
+    }
+}
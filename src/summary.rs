@@ -0,0 +1,407 @@
+use crate::member;
+use crate::report::SectionFinding;
+use crate::somato::{self, Analysis, LocationSummary};
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+
+/// Output format of the summary, decides how lists are rendered
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryFormat {
+    #[default]
+    Markdown,
+    Text,
+}
+
+const LOCATION_START: &str = "{#location}";
+const LOCATION_END: &str = "{/location}";
+
+const TEMPLATE_MARKDOWN: &str = "# Distribution {date}
+
+{#location}
+## {location}
+
+- Members: {members}
+- Shares: {shares}
+- Collected this week: {collected}
+- Jokers ({jokers}):
+{joker_names}
+- Substitutions ({substitutions}):
+{substitution_names}
+- Open issues ({issue_count}):
+{issues}
+
+{/location}
+## General
+
+- Shares over all locations: {total_shares}
+- Substitutions: {substitutions}
+- Open issues ({issue_count}):
+{issues}
+";
+
+const TEMPLATE_TEXT: &str = "Distribution {date}
+
+{#location}
+{location}
+  Members: {members}
+  Shares: {shares}
+  Collected this week: {collected}
+  Jokers ({jokers}):
+{joker_names}
+  Substitutions ({substitutions}):
+{substitution_names}
+  Open issues ({issue_count}):
+{issues}
+
+{/location}
+General
+  Shares over all locations: {total_shares}
+  Substitutions: {substitutions}
+  Open issues ({issue_count}):
+{issues}
+";
+
+/// Built in template for the given format
+pub fn default_template(format: SummaryFormat) -> &'static str {
+    match format {
+        SummaryFormat::Markdown => TEMPLATE_MARKDOWN,
+        SummaryFormat::Text => TEMPLATE_TEXT,
+    }
+}
+
+fn render_list(items: &[String], format: SummaryFormat) -> String {
+    let prefix = match format {
+        SummaryFormat::Markdown => "  - ",
+        SummaryFormat::Text => "    * ",
+    };
+    if items.is_empty() {
+        return format!("{prefix}none");
+    }
+    items
+        .iter()
+        .map(|i| format!("{prefix}{i}"))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn issues(findings: &[SectionFinding], section: Option<&str>) -> Vec<String> {
+    findings
+        .iter()
+        .filter(|f| f.section.as_deref() == section)
+        .map(|f| format!("{:?}: {}", f.finding.severity, f.finding.message))
+        .collect()
+}
+
+/// The shares of all given share types, e.g. "big 2, small 0"
+fn render_shares(shares: &member::Shares, types: &[&str]) -> String {
+    types
+        .iter()
+        .map(|t| format!("{t} {}", shares.get(t)))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Replace all placeholders "{name}" with their values
+fn fill(template: &str, values: &[(String, String)]) -> String {
+    let mut result = template.to_string();
+    for (name, value) in values {
+        result = result.replace(&format!("{{{name}}}"), value);
+    }
+    result
+}
+
+fn render_location(
+    template: &str,
+    location: &LocationSummary,
+    findings: &[SectionFinding],
+    types: &[&str],
+    format: SummaryFormat,
+) -> String {
+    let section = somato::section_title(&location.location);
+    let issues = issues(findings, Some(&section));
+    let joker_names: Vec<String> = location
+        .jokers
+        .iter()
+        .map(|j| format!("{} {}", j.forename, j.surname))
+        .collect();
+    let substitution_names: Vec<String> = location
+        .substitutions
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let mut values = vec![
+        ("location", format!("{:?}", location.location)),
+        ("members", location.members.len().to_string()),
+        ("shares", render_shares(&location.shares, types)),
+        ("collected", render_shares(&location.collected, types)),
+        ("jokers", location.jokers.len().to_string()),
+        ("joker_names", render_list(&joker_names, format)),
+        ("substitutions", location.substitutions.len().to_string()),
+        (
+            "substitution_names",
+            render_list(&substitution_names, format),
+        ),
+        ("issue_count", issues.len().to_string()),
+        ("issues", render_list(&issues, format)),
+    ]
+    .into_iter()
+    .map(|(name, value)| (name.to_string(), value))
+    .collect::<Vec<(String, String)>>();
+    for t in types.iter() {
+        values.push((t.to_string(), location.shares.get(t).to_string()));
+        values.push((
+            format!("collected_{t}"),
+            location.collected.get(t).to_string(),
+        ));
+    }
+    fill(template, &values)
+}
+
+/// Render the summary of the analysis with the given template.
+/// The part between "{#location}" and "{/location}" is repeated for every
+/// location, outside of it the placeholders refer to the whole analysis.
+pub fn render_summary(
+    analysis: &Analysis,
+    template: &str,
+    format: SummaryFormat,
+) -> Result<String> {
+    let (head, block, tail) = match (
+        template.find(LOCATION_START),
+        template.find(LOCATION_END),
+    ) {
+        (Some(start), Some(end)) if start < end => (
+            &template[..start],
+            &template[start + LOCATION_START.len()..end],
+            &template[end + LOCATION_END.len()..],
+        ),
+        (None, None) => (template, "", ""),
+        _ => {
+            return Err(anyhow!(
+                "Summary template needs both {LOCATION_START} and {LOCATION_END}"
+            ));
+        }
+    };
+    // Line breaks right after the markers belong to them
+    let block = block.strip_prefix('\n').unwrap_or(block);
+    let tail = tail.strip_prefix('\n').unwrap_or(tail);
+
+    let types = analysis.total_shares.types();
+    let mut locations = String::new();
+    for location in analysis.locations.iter() {
+        locations.push_str(&render_location(
+            block,
+            location,
+            &analysis.findings,
+            &types,
+            format,
+        ));
+    }
+    let issues = issues(&analysis.findings, None);
+    let mut values = vec![
+        ("date", analysis.date.to_string()),
+        ("warnings", analysis.warnings.to_string()),
+        (
+            "substitutions",
+            analysis
+                .locations
+                .iter()
+                .map(|l| l.substitutions.len())
+                .sum::<usize>()
+                .to_string(),
+        ),
+        (
+            "total_shares",
+            render_shares(&analysis.total_shares, &types),
+        ),
+        ("issue_count", issues.len().to_string()),
+        ("issues", render_list(&issues, format)),
+    ]
+    .into_iter()
+    .map(|(name, value)| (name.to_string(), value))
+    .collect::<Vec<(String, String)>>();
+    for t in types.iter() {
+        values.push((
+            format!("total_{t}"),
+            analysis.total_shares.get(t).to_string(),
+        ));
+    }
+    Ok(format!(
+        "{}{}{}",
+        fill(head, &values),
+        locations,
+        fill(tail, &values)
+    ))
+}
+
+/// Write the summary into a file, the template is read from a file if given
+pub fn write_summary(
+    analysis: &Analysis,
+    template_file: Option<&str>,
+    format: SummaryFormat,
+    file: &str,
+) -> Result<()> {
+    let template = match template_file {
+        Some(t) => std::fs::read_to_string(t).map_err(|e| {
+            anyhow!(format!("Error {e} while reading summary template {t}"))
+        })?,
+        None => default_template(format).to_string(),
+    };
+    let summary = render_summary(analysis, &template, format)?;
+    std::fs::write(file, summary).map_err(|e| {
+        anyhow!(format!("Error {e} while writing summary file {file}"))
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod summary_tests {
+
+    use super::*;
+    use crate::location::Location;
+    use crate::member::{BIG, SMALL};
+    use crate::report::{Finding, Severity};
+    use crate::substitution::Substitution;
+    use crate::test_common::test_common::*;
+    use chrono::NaiveDate;
+
+    fn gen_finding(section: Option<&str>, message: &str) -> SectionFinding {
+        SectionFinding {
+            section: section.map(|s| s.to_string()),
+            finding: Finding {
+                severity: Severity::Warning,
+                message: message.to_string(),
+                source: None,
+            },
+        }
+    }
+
+    fn gen_analysis() -> Analysis {
+        let members = gen_members().to_vec();
+        let summary =
+            |location: Location, jokers, substitutions| LocationSummary {
+                location,
+                members: members.clone(),
+                shares: member::count_shares(&members),
+                jokers,
+                collected: member::Shares::from([(BIG, 3), (SMALL, 3)]),
+                expected: member::Shares::from([(BIG, 3), (SMALL, 3)]),
+                tick_off: member::Shares::from([(BIG, 3), (SMALL, 3)]),
+                substitutions,
+                redistribution: Default::default(),
+            };
+        Analysis {
+            date: NaiveDate::from_ymd_opt(2025, 11, 7).unwrap(),
+            locations: vec![
+                summary(
+                    Location::Perouse,
+                    vec![gen_joker_b()],
+                    vec![Substitution {
+                        date: NaiveDate::from_ymd_opt(2025, 11, 7).unwrap(),
+                        surname: "Testeress".to_string(),
+                        forename: "Cloe".to_string(),
+                        substitute: "Nachbarin, N.".to_string(),
+                        line: 2,
+                    }],
+                ),
+                summary(Location::Gerlingen, vec![], vec![]),
+            ],
+            total_shares: member::Shares::from([(BIG, 10), (SMALL, 10)]),
+            warnings: 2,
+            findings: vec![
+                gen_finding(None, "Duplicated surname"),
+                gen_finding(Some("Analysis for: Gerlingen"), "Missing"),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_render_summary_markdown() {
+        let summary = render_summary(
+            &gen_analysis(),
+            default_template(SummaryFormat::Markdown),
+            SummaryFormat::Markdown,
+        )
+        .unwrap();
+        let expected_perouse = "## Perouse
+
+- Members: 3
+- Shares: big 5, small 5
+- Collected this week: big 3, small 3
+- Jokers (1):
+  - Bob Tester
+- Substitutions (1):
+  - Nachbarin, N. for Cloe Testeress
+- Open issues (0):
+  - none
+";
+        assert!(summary.starts_with("# Distribution 2025-11-07\n\n## Perouse"));
+        assert!(summary.contains(expected_perouse));
+        assert!(summary.contains("## Gerlingen"));
+        assert!(summary.contains("  - Warning: Missing\n"));
+        assert!(
+            summary.contains("- Shares over all locations: big 10, small 10")
+        );
+        assert!(summary.contains("- Substitutions: 1\n"));
+        assert!(summary.ends_with("  - Warning: Duplicated surname\n"));
+        assert!(!summary.contains('{'));
+    }
+
+    #[test]
+    fn test_render_summary_text() {
+        let summary = render_summary(
+            &gen_analysis(),
+            default_template(SummaryFormat::Text),
+            SummaryFormat::Text,
+        )
+        .unwrap();
+        assert!(summary.contains("Perouse\n  Members: 3\n"));
+        assert!(summary.contains("    * Bob Tester\n"));
+        assert!(!summary.contains('{'));
+    }
+
+    #[test]
+    fn test_render_summary_custom() {
+        let analysis = gen_analysis();
+        let format = SummaryFormat::Text;
+        let template = "{date}: {#location}{location} {jokers}, {/location}end";
+        assert_eq!(
+            render_summary(&analysis, template, format).unwrap(),
+            "2025-11-07: Perouse 1, Gerlingen 0, end"
+        );
+        assert_eq!(
+            render_summary(&analysis, "{warnings} warnings", format).unwrap(),
+            "2 warnings"
+        );
+        assert!(render_summary(&analysis, "{#location}", format).is_err());
+        assert!(
+            render_summary(&analysis, "{/location}{#location}", format)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_write_summary() {
+        let file = std::env::temp_dir().join("somato_test_summary.txt");
+        let file = file.to_str().unwrap();
+        let template = std::env::temp_dir().join("somato_test_template.txt");
+        std::fs::write(&template, "Date {date}").unwrap();
+        write_summary(
+            &gen_analysis(),
+            template.to_str(),
+            SummaryFormat::Text,
+            file,
+        )
+        .unwrap();
+        assert_eq!(std::fs::read_to_string(file).unwrap(), "Date 2025-11-07");
+        assert!(
+            write_summary(
+                &gen_analysis(),
+                Some("missing_template.txt"),
+                SummaryFormat::Text,
+                file,
+            )
+            .is_err()
+        );
+    }
+}
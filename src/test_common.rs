@@ -1,26 +1,27 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 pub mod test_common {
     use crate::joker::Joker;
     use crate::location::Location;
-    use crate::member::Member;
+    use crate::member::{BIG, Member, MemberStatus, SMALL, Shares};
     use crate::tickoff::TickOffItem;
     use chrono::NaiveDate;
 
     pub fn gen_toi_ok() -> [TickOffItem; 3] {
         let a = TickOffItem {
             name: "Testerin, A.".to_string(),
-            big: 2,
-            small: 0,
+            shares: Shares::from([(BIG, 2), (SMALL, 0)]),
+            source: None,
         };
         let b = TickOffItem {
             name: "Tester, B.".to_string(),
-            big: 0,
-            small: 2,
+            shares: Shares::from([(BIG, 0), (SMALL, 2)]),
+            source: None,
         };
         let c = TickOffItem {
             name: "Testeress, C.".to_string(),
-            big: 3,
-            small: 3,
+            shares: Shares::from([(BIG, 3), (SMALL, 3)]),
+            source: None,
         };
         [a, b, c]
     }
@@ -28,18 +29,18 @@ pub mod test_common {
     pub fn gen_toi_duplication() -> [TickOffItem; 3] {
         let a = TickOffItem {
             name: "Testerin, A.".to_string(),
-            big: 2,
-            small: 0,
+            shares: Shares::from([(BIG, 2), (SMALL, 0)]),
+            source: None,
         };
         let b = TickOffItem {
             name: "Tester, B.".to_string(),
-            big: 0,
-            small: 2,
+            shares: Shares::from([(BIG, 0), (SMALL, 2)]),
+            source: None,
         };
         let c = TickOffItem {
             name: "Testerin, A.".to_string(),
-            big: 0,
-            small: 3,
+            shares: Shares::from([(BIG, 0), (SMALL, 3)]),
+            source: None,
         };
         [a, b, c]
     }
@@ -47,23 +48,23 @@ pub mod test_common {
     pub fn gen_toi_fail() -> [TickOffItem; 4] {
         let a = TickOffItem {
             name: "Testerin, A.".to_string(),
-            big: 2,
-            small: 0,
+            shares: Shares::from([(BIG, 2), (SMALL, 0)]),
+            source: None,
         };
         let a_small = TickOffItem {
             name: "testerin, a.".to_string(),
-            big: 2,
-            small: 0,
+            shares: Shares::from([(BIG, 2), (SMALL, 0)]),
+            source: None,
         };
         let b = TickOffItem {
             name: "Tester, B.".to_string(),
-            big: 0,
-            small: 2,
+            shares: Shares::from([(BIG, 0), (SMALL, 2)]),
+            source: None,
         };
         let c = TickOffItem {
             name: "Fail".to_string(),
-            big: 2,
-            small: 4,
+            shares: Shares::from([(BIG, 2), (SMALL, 4)]),
+            source: None,
         };
         [a, a_small, b, c]
     }
@@ -77,7 +78,7 @@ pub mod test_common {
             2,
             0,
             Location::Perouse,
-            true,
+            MemberStatus::Active,
         );
         let b = Member::new_from_values(
             "EV-2",
@@ -87,7 +88,7 @@ pub mod test_common {
             0,
             2,
             Location::Perouse,
-            true,
+            MemberStatus::Active,
         );
         let c = Member::new_from_values(
             "EV-3",
@@ -97,7 +98,7 @@ pub mod test_common {
             3,
             3,
             Location::Perouse,
-            true,
+            MemberStatus::Active,
         );
         [a, b, c]
     }
@@ -111,7 +112,7 @@ pub mod test_common {
             2,
             0,
             Location::Perouse,
-            true,
+            MemberStatus::Active,
         );
         let b = Member::new_from_values(
             "EV-2",
@@ -121,7 +122,7 @@ pub mod test_common {
             0,
             2,
             Location::Perouse,
-            true,
+            MemberStatus::Active,
         );
         let c = Member::new_from_values(
             "EV-3",
@@ -131,48 +132,42 @@ pub mod test_common {
             3,
             3,
             Location::Perouse,
-            true,
+            MemberStatus::Active,
         );
         [a.clone(), a.clone(), b, c, a]
     }
 
     pub fn gen_joker_a() -> Joker {
-        let j = Joker {
+        Joker {
             date: NaiveDate::from_ymd_opt(1, 1, 1).unwrap(),
             surname: "Testerin".to_string(),
             forename: "Alice".to_string(),
             warning: 0,
             location: Location::Perouse,
-            big: 2,
-            small: 0,
+            shares: Shares::from([(BIG, 2), (SMALL, 0)]),
             line: 88,
-        };
-        j
+        }
     }
     pub fn gen_joker_b() -> Joker {
-        let j = Joker {
+        Joker {
             date: NaiveDate::from_ymd_opt(1, 1, 1).unwrap(),
             surname: "Tester".to_string(),
             forename: "Bob".to_string(),
             warning: 0,
             location: Location::Perouse,
-            big: 0,
-            small: 2,
+            shares: Shares::from([(BIG, 0), (SMALL, 2)]),
             line: 88,
-        };
-        j
+        }
     }
     pub fn gen_joker_c() -> Joker {
-        let j = Joker {
+        Joker {
             date: NaiveDate::from_ymd_opt(1, 1, 1).unwrap(),
             surname: "Testeress".to_string(),
             forename: "Cloe".to_string(),
             warning: 0,
             location: Location::Perouse,
-            big: 3,
-            small: 3,
+            shares: Shares::from([(BIG, 3), (SMALL, 3)]),
             line: 88,
-        };
-        j
+        }
     }
 }
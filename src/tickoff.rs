@@ -1,55 +1,49 @@
 // use crate::location::Location;
+use crate::input;
+use crate::joker;
 use crate::location::Location;
 use crate::member;
+use crate::report::{Reporter, SourceRef};
+use crate::substitution::Substitution;
 use anyhow::{Result, anyhow};
-use calamine::{Data, DataType, Reader, Xlsx, open_workbook};
-use chrono;
-use colored::Colorize;
+use calamine::{Data, DataType};
 use lazy_regex;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
 /// Describes a single tick off item
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TickOffItem {
     // pub date: NaiveDate,
     // pub surname: String,
     // pub forename: String,
     pub name: String,
     // pub location: Location,
-    pub big: u32,
-    pub small: u32,
-    // pub line: u32,
+    pub shares: member::Shares,
+    /// Cell of the name in the tickoff list
+    pub source: Option<SourceRef>,
 }
 
 impl TickOffItem {
     /// Try to generate a new Tick Off Item from parsed data.
+    /// The item holds the amount of the one share type of its block.
     pub fn try_new(
         name: &Data,
-        big: Option<&Data>,
-        small: Option<&Data>,
+        share_type: &str,
+        amount: &Data,
     ) -> Result<Self> {
-        // println!("Creating new entry with {:?} {:?} {:?}", name, big, small);
+        // println!("Creating new entry with {:?} {:?}", name, amount);
         let name = name
             .as_string()
             .ok_or_else(|| anyhow!("Cannot parse name \"{}\"", name));
         // .unwrap_or(format!("Error while parsing \"{:?}\"", name));
-        let big = match big {
-            Some(i) => i.as_i64().unwrap_or(88) as u32,
-            None => 0,
-        };
-        let small = match small {
-            Some(i) => i.as_i64().unwrap_or(88) as u32,
-            None => 0,
-        };
+        let mut shares = member::Shares::default();
+        shares.set(share_type, amount.as_i64().unwrap_or(88) as u32);
         let item = TickOffItem {
             name: name?,
-            big,
-            small,
+            shares,
+            source: None,
         };
-        // println!(
-        //     "Creating new entry with {:?} {:?} {:?}",
-        //     item.name, item.big, item.small
-        // );
         // println!("{item:?}");
         Ok(item)
     }
@@ -61,14 +55,7 @@ impl TickOffItem {
 /// start with umlauts.
 pub fn check_name_with_initial(name: &str) -> bool {
     let re = lazy_regex::regex!(r"^[A-ZÄÖÜa-zäöü\- ]*, [[:alpha:]].$");
-    let rematch = re.is_match(name);
-    if !rematch {
-        println!(
-            "{}",
-            format!("    Malformed name \"{}\"", name).bright_red()
-        );
-    };
-    rematch
+    re.is_match(name)
 }
 
 /// Split the given name into surname and first part of forename
@@ -109,50 +96,126 @@ pub fn check_name_equality(
     false
 }
 
+/// Check the name with initial against the contract holder and all
+/// co-holders of the member. For shared contracts either household may be
+/// listed.
+pub fn check_member_name(
+    member: &member::Member,
+    name_with_initial: &str,
+) -> bool {
+    member
+        .holders()
+        .iter()
+        .any(|(s, f)| check_name_equality(s, f, name_with_initial))
+}
+
+/// Checks that shared contracts with alternating households are collected by
+/// the household whose turn it is at the date.
+/// Members with a substitution are skipped, their substitute is listed under
+/// the name of the contract holder.
+/// Returns the amount of warnings.
+pub fn check_schedules(
+    members: &member::MemberList,
+    tickoff: &TickOffList,
+    substitutions: &[Substitution],
+    date: &chrono::NaiveDate,
+    reporter: &mut dyn Reporter,
+) -> usize {
+    let mut warnings = 0;
+    for m in members.iter() {
+        let alternating = m
+            .co_holders
+            .iter()
+            .any(|c| c.schedule != member::Schedule::Always);
+        if !alternating
+            || substitutions
+                .iter()
+                .any(|s| m.is_holder(&s.surname, &s.forename))
+        {
+            continue;
+        }
+        let (surname, forename) = m.collector_at(date);
+        for tick in tickoff.iter() {
+            if !check_member_name(m, &tick.name)
+                || check_name_equality(surname, forename, &tick.name)
+            {
+                continue;
+            }
+            reporter.warning_at(
+                format!(
+                    "\"{}\" collected for {} at {date}, but it is the turn of \
+                     {forename} {surname}",
+                    tick.name, m.contract_no
+                ),
+                tick.source.clone(),
+            );
+            warnings += 1;
+        }
+    }
+    warnings
+}
+
 /// Checks if all members are mentioned in the tickoff list.
-/// If they are not this is an idication of a joker.
+/// Members with a joker must not be listed, all others must be listed.
+/// The jokers are expected to be already filtered by date.
+/// Malformed names are reported once and not matched against the members.
 /// Number of warnings is returned with an Option<usize>
 pub fn check_for_members_in_tickoff_list(
     members: &member::MemberList,
     tickoff: &TickOffList,
+    jokers: &[joker::Joker],
+    reporter: &mut dyn Reporter,
 ) -> Result<Option<usize>> {
-    println!("Checking tickoff list for missig members.");
-    println!(
-        "  Got {} members and {} tickoff to check",
+    reporter.debug("Checking tickoff list for missig members.");
+    reporter.debug(&format!(
+        "  Got {} members, {} tickoff and {} jokers to check",
         members.len(),
-        tickoff.len()
-    );
+        tickoff.len(),
+        jokers.len()
+    ));
     let mut warnings = None;
-    let tickoffset: HashSet<TickOffItem> = HashSet::from_iter(tickoff.clone());
+    for tick in tickoff.iter() {
+        if !check_name_with_initial(&tick.name) {
+            reporter.warning_at(
+                format!("Malformed name \"{}\"", tick.name),
+                tick.source.clone(),
+            );
+            warnings = match warnings {
+                Some(w) => Some(w + 1),
+                None => Some(1),
+            };
+        }
+    }
+    let tickoffset: HashSet<TickOffItem> = HashSet::from_iter(
+        tickoff
+            .iter()
+            .filter(|t| check_name_with_initial(&t.name))
+            .cloned(),
+    );
 
-    'outer: for member in members.iter() {
+    for member in members.iter() {
         // println!("Checking member {member}");
-
+        let mut listed = false;
         for tick in tickoffset.iter() {
-            if !check_name_with_initial(&tick.name) {
-                warnings = match warnings {
-                    Some(w) => Some(w + 1),
-                    None => Some(1),
-                };
-                continue;
-            }
-            if check_name_equality(
-                &member.surname,
-                &member.forename,
-                &tick.name,
-            ) {
+            if check_member_name(member, &tick.name) {
                 // println!("Found {}", member.surname);
-                continue 'outer;
+                listed = true;
+                break;
             }
         }
-        println!(
-            "{}",
-            format!(
-                "    Cannot find member \"{}\" in tickoff list. Joker?",
+        let joker = member::has_joker(member, jokers);
+        let message = match (listed, joker) {
+            (true, false) | (false, true) => continue,
+            (true, true) => format!(
+                "Member \"{}\" has a joker but is in tickoff list",
                 member
-            )
-            .bright_blue()
-        );
+            ),
+            (false, false) => format!(
+                "Cannot find member \"{}\" in tickoff list and no joker is set",
+                member
+            ),
+        };
+        reporter.error_at(message, Some(member.source(None)));
         warnings = match warnings {
             Some(w) => Some(w + 1),
             None => Some(1),
@@ -162,84 +225,58 @@ pub fn check_for_members_in_tickoff_list(
 }
 
 /// Checks if all members are mentioned in the tickoff list.
+/// Malformed names are skipped, check_for_members_in_tickoff_list reports
+/// them.
 /// Number of warnings is returned with an Option<usize>
 pub fn check_tickoff_list_against_members(
     members: &member::MemberList,
     tickoff: &TickOffList,
+    reporter: &mut dyn Reporter,
 ) -> Result<Option<usize>> {
-    println!("Checking members for missig enries in tickoff list.");
-    println!(
+    reporter.debug("Checking members for missig enries in tickoff list.");
+    reporter.debug(&format!(
         "  Got {} members and {} tickoff to check",
         members.len(),
         tickoff.len()
-    );
+    ));
     let mut warnings = None;
 
     'outer: for tick in tickoff.iter() {
+        if !check_name_with_initial(&tick.name) {
+            continue;
+        }
         for member in members.iter() {
             // println!("Checking member {member}");
 
-            // Ignore inactive members
-            if !member.active {
+            // Ignore members that do not collect
+            if !member.status.on_tick_off_list() {
                 continue;
             }
 
-            if !check_name_with_initial(&tick.name) {
-                warnings = match warnings {
-                    Some(w) => Some(w + 1),
-                    None => Some(1),
-                };
-                // Name is malformed - skip furhter analysis
-                // No additonal warning check_name_with_initial already complains
-                continue;
-            }
-            if check_name_equality(
-                &member.surname,
-                &member.forename,
-                &tick.name,
-            ) {
+            if check_member_name(member, &tick.name) {
                 // println!("Found {}", member.surname);
-                if member.big != tick.big {
+                for (size, expected, listed) in
+                    member.shares.differences(&tick.shares)
+                {
                     warnings = match warnings {
                         Some(w) => Some(w + 1),
                         None => Some(1),
                     };
-                    println!(
-                        "{}",
+                    reporter.error_at(
                         format!(
-                            // "    Tickoff size for big portion {} {} does not match member list: {} {}",
-                            // member.surname, member.forename, member.big, tick.big
-                            "    Tickoff size for big portion {} does not match member list: {} {}",
-                            tick.name, member.big, tick.big
-                        )
-                        .bright_red()
+                            "Tickoff size for {} portion {} does not match \
+                             member list: {} {}",
+                            size, tick.name, expected, listed
+                        ),
+                        tick.source.clone(),
                     );
-                    // warnings += 1;
-                }
-                if member.small != tick.small {
-                    warnings = match warnings {
-                        Some(w) => Some(w + 1),
-                        None => Some(1),
-                    };
-                    println!(
-                        "{}",
-                        format!(
-                            // "    Tickoff size for small portion {} {} does not match member list: {} {}",
-                            // member.surname, member.forename, member.small, tick.small
-                            "    Tickoff size for small portion {} does not match member list: {} {}",
-                            tick.name, member.small, tick.small
-                        )
-                        .bright_red()
-                    );
-                    // warnings += 1;
                 }
                 continue 'outer;
             }
         }
-        println!(
-            "{}",
-            format!("    Cannot find item \"{}\" in member list", tick.name)
-                .bright_red()
+        reporter.error_at(
+            format!("Cannot find item \"{}\" in member list", tick.name),
+            tick.source.clone(),
         );
         warnings = match warnings {
             Some(w) => Some(w + 1),
@@ -249,191 +286,345 @@ pub fn check_tickoff_list_against_members(
     Ok(warnings)
 }
 
+/// A member that shows up on the tickoff list of another location.
+#[derive(Debug, Clone)]
+pub struct WrongLocation {
+    pub member: member::Member,
+    pub name: String,
+    pub listed_at: Location,
+}
+
+/// Checks all tickoff lists together for members listed at the wrong
+/// location.
+/// An entry counts as wrong location when no member of the list's own
+/// location matches it, but a member of another location does and that
+/// member is not listed at their own location.
+pub fn check_wrong_location(
+    members: &member::MemberList,
+    tickoffs: &[(Location, TickOffList)],
+    reporter: &mut dyn Reporter,
+) -> Vec<WrongLocation> {
+    reporter.debug("Checking tickoff lists for members at the wrong location.");
+    let is_listed = |m: &member::Member, list: &TickOffList| {
+        list.iter().any(|t| {
+            split_name(&t.name).is_some() && check_member_name(m, &t.name)
+        })
+    };
+    let mut result = Vec::new();
+    for (location, tickoff) in tickoffs.iter() {
+        for tick in tickoff.iter() {
+            // Malformed names are reported by the per location checks
+            if split_name(&tick.name).is_none() {
+                continue;
+            }
+            let matches = |m: &&member::Member| {
+                m.status.on_tick_off_list() && check_member_name(m, &tick.name)
+            };
+            if members
+                .iter()
+                .filter(|m| m.location == *location)
+                .any(|m| matches(&m))
+            {
+                continue;
+            }
+            let Some(member) = members
+                .iter()
+                .filter(|m| m.location != *location)
+                .filter(matches)
+                .find(|m| {
+                    !tickoffs
+                        .iter()
+                        .filter(|(l, _)| *l == m.location)
+                        .any(|(_, list)| is_listed(m, list))
+                })
+            else {
+                continue;
+            };
+            reporter.error_at(
+                format!(
+                    "Member \"{}\" from {:?} is listed at wrong location {:?}",
+                    member, member.location, location
+                ),
+                Some(member.source(Some(member::COLUMN_LOCATION))),
+            );
+            result.push(WrongLocation {
+                member: member.clone(),
+                name: tick.name.clone(),
+                listed_at: location.clone(),
+            });
+        }
+    }
+    result
+}
+
 /// Container type for the TickOffList
 pub type TickOffList = Vec<TickOffItem>;
 
-/// Parse tickoff list from filename and location
-pub fn tick_off_list(
+/// The entries of a tickoff sheet and the sums written below the blocks
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TickOffSheet {
+    pub items: TickOffList,
+    pub sums: member::Shares,
+}
+
+/// Column of the names of the share type in the tickoff sheet of the location
+fn tickoff_column(
+    share_type: &member::ShareType,
+    location: &Location,
+) -> usize {
+    // Historic reasos, the table for Perouse is shifted by one cell after the
+    // first block
+    match location {
+        Location::Perouse if share_type.tickoff_column > 0 => {
+            share_type.tickoff_column as usize - 1
+        }
+        _ => share_type.tickoff_column as usize,
+    }
+}
+
+/// Parse the tickoff sheet of the location from filename.
+/// Every share type has its own block of names and amounts, followed by a
+/// row with the sum.
+pub fn tick_off_sheet(
     tickoff_file: &str,
     location: &Location,
-) -> Result<TickOffList> {
-    println!("Parsing tickoff list");
-    let mut excel: Xlsx<_> = open_workbook(tickoff_file).map_err(|e| {
-        anyhow!(format!(
-            "Error {e} while loading tickoff file {tickoff_file}"
-        ))
-    })?;
+    format: Option<input::InputFormat>,
+    share_types: &[member::ShareType],
+) -> Result<TickOffSheet> {
     let mut tick_off_list = vec![];
 
-    // Historic reasos, the table for Perouse is shifted by one cell
-    let offset = match location {
-        Location::Perouse => 0,
-        _ => 1,
-    };
-    let mut sum_big: u32 = 0;
-    let mut sum_small: u32 = 0;
-    if let Ok(r) = excel.worksheet_range(location.to_short()) {
-        for row in r.rows().skip(7).take(100) {
-            // println!(
-            //     "Big: \"{}\" {}\" Small: {}\" {}\"",
-            //     row[0],
-            //     row[1],
-            //     row[5 + offset],
-            //     row[6 + offset],
-            // );
-            if let Data::DateTime(date) = row[0] {
-                println!(
-                    "{}",
-                    chrono::NaiveDate::from(date.as_datetime().unwrap())
-                );
-            }
-            let (mut big_done, mut small_done) = (false, false);
-            let name_big = &row[0];
-            let amount_big = &row[1];
-
-            let name_small = &row[4 + offset];
-            let amount_small = &row[5 + offset];
-
-            // Try to parse_items
-            let item_big =
-                TickOffItem::try_new(name_big, Some(amount_big), None);
-            let item_small =
-                TickOffItem::try_new(name_small, None, Some(amount_small));
-
-            // Check if we reached the end of the list
-            if let Ok(item) = item_big {
-                tick_off_list.push(item);
-            } else {
-                if let Some(s) = amount_big.as_i64() {
-                    sum_big = s as u32
-                };
-                // println!("Error while parsing big: {item_big:?}");
-                big_done = true;
-            }
+    let mut sums = member::Shares::default();
+    if let Some(r) =
+        input::read_sheet(tickoff_file, location.to_short(), format)?
+    {
+        let (start_row, start_col) = r.start().unwrap_or((0, 0));
+        let cell = |row: usize, col: usize| {
+            Some(SourceRef::new(
+                location.to_short(),
+                start_row + row as u32 + 1,
+                Some(start_col + col as u32),
+            ))
+        };
+        for (index, row) in r.rows().enumerate().skip(7).take(100) {
+            let mut done = 0;
+            for share_type in share_types.iter() {
+                let column = tickoff_column(share_type, location);
+                let name = row.get(column).unwrap_or(&Data::Empty);
+                let amount = row.get(column + 1).unwrap_or(&Data::Empty);
 
-            // Check if we reached the end of the list
-            if let Ok(item) = item_small {
-                tick_off_list.push(item);
-            } else {
-                if let Some(s) = amount_small.as_i64() {
-                    sum_small = s as u32
-                };
-                // println!("Error while parsing small: {item_small:?}");
-                small_done = true;
+                // Check if we reached the end of the block, the row after
+                // it holds the sum
+                if let Ok(mut item) =
+                    TickOffItem::try_new(name, &share_type.name, amount)
+                {
+                    item.source = cell(index, column);
+                    tick_off_list.push(item);
+                } else {
+                    if let Some(s) = amount.as_i64() {
+                        sums.set(&share_type.name, s as u32)
+                    };
+                    done += 1;
+                }
             }
-
-            if big_done && small_done {
+            if done == share_types.len() {
                 // println!("Items exhausted");
-                // println!("Parsed {amount_big:?} big amount");
-                // println!("Parsed {amount_small:?} small amount");
                 break;
             }
-            // let joker = Joker::new(
-            //     &date, &name, &forename, warning, &location, big, small, line,
-            // )?;
-            // jokers.push(joker);
-            // line += 1;
         }
     }
-    let all_big = get_amount_big(&tick_off_list);
-    let all_small = get_amount_small(&tick_off_list);
-    println!("  Parsed {sum_big} big portions");
-    println!("  Parsed {sum_small} small amount");
-    // assert_eq!(
-    //     sum_big, all_big,
-    //     "Amount for big in tickoff list does not match"
-    // );
-    // assert_eq!(
-    //     sum_small, all_small,
-    //     "Amount for small in tickoff list does not match"
-    // );
-    if sum_big != all_big {
-        println!(
-            "{}",
-            format!(
-                "    Amount for big in tickoff list does not match {} vs. {}",
-                sum_big, all_big,
-            )
-            .bright_red()
-        );
-    }
-    if sum_small != all_small {
-        println!(
-            "{}",
-            format!(
-                "    Amount for small in tickoff list does not match {} vs. {}",
-                sum_small, all_small,
-            )
-            .bright_red()
-        );
+    Ok(TickOffSheet {
+        items: tick_off_list,
+        sums,
+    })
+}
+
+/// Warn when the sums of the tickoff sheet do not match its entries.
+/// Returns the amount of warnings.
+pub fn check_sums(
+    sheet: &TickOffSheet,
+    share_types: &[member::ShareType],
+    reporter: &mut dyn Reporter,
+) -> usize {
+    let mut warnings = 0;
+    let all = get_amounts(&sheet.items);
+    for share_type in share_types.iter() {
+        let (sum, listed) =
+            (sheet.sums.get(&share_type.name), all.get(&share_type.name));
+        reporter.debug(&format!("  Parsed {sum} {} portions", share_type.name));
+        if sum != listed {
+            reporter.warning(format!(
+                "Amount for {} in tickoff list does not match {} vs. {}",
+                share_type.name, sum, listed,
+            ));
+            warnings += 1;
+        }
     }
-    Ok(tick_off_list)
+    warnings
 }
 
-/// Helper function to get the amout of big collectors
-pub fn get_amount_big(tick_off_list: &[TickOffItem]) -> u32 {
-    tick_off_list
-        .iter()
-        .filter(|x| x.big > 0)
-        .map(|x| x.big)
-        .sum::<u32>()
+/// Helper function to get the amounts of all collectors per share type
+pub fn get_amounts(tick_off_list: &[TickOffItem]) -> member::Shares {
+    let mut amounts = member::Shares::default();
+    for item in tick_off_list.iter() {
+        amounts += &item.shares;
+    }
+    amounts
 }
 
-/// Helper function to get the amout of big collectors
-pub fn get_amount_small(tick_off_list: &[TickOffItem]) -> u32 {
-    tick_off_list
-        .iter()
-        .filter(|x| x.small > 0)
-        .map(|x| x.small)
-        .sum::<u32>()
+/// Checks that the amounts in the tickoff list match the expected shares.
+/// The expected shares are the shares of all members that collect this week.
+/// Number of warnings is returned with an Option<usize>
+pub fn check_share_amounts(
+    shares: &member::Shares,
+    tickoff: &TickOffList,
+    reporter: &mut dyn Reporter,
+) -> Result<Option<usize>> {
+    reporter.debug("Checking tickoff amounts against shares.");
+    let mut warnings = None;
+    for (size, expected, listed) in shares.differences(&get_amounts(tickoff)) {
+        reporter.error(format!(
+            "Tickoff amount for {} shares does not match: expected {} \
+             listed {}",
+            size, expected, listed
+        ));
+        warnings = match warnings {
+            Some(w) => Some(w + 1),
+            None => Some(1),
+        };
+    }
+    Ok(warnings)
 }
 
 /// Deduplicate the Tickoff list.
-/// Summarise items where necessary.
+/// A name that appears in the blocks of several share types, e.g. once in the
+/// big block and once in the small block, is a combined share and gets
+/// merged into one item.
+/// A name that appears twice in the same block stays a separate item, as
+/// "Surname, I." can stand for several members. No entry is dropped, the
+/// amounts of the list stay the same.
 pub fn deduplicate(list: &[TickOffItem]) -> Vec<TickOffItem> {
     let mut result: Vec<TickOffItem> = Vec::new();
     for element in list {
-        match result.iter().position(|x| x.name == element.name) {
-            Some(x) => {
-                result[x].big += element.big;
-                result[x].small += element.small;
-            }
+        let combined = result.iter().position(|x| {
+            x.name == element.name
+                && element
+                    .shares
+                    .iter()
+                    .all(|(t, a)| a == 0 || x.shares.get(t) == 0)
+        });
+        match combined {
+            Some(x) => result[x].shares += &element.shares,
             None => result.push(element.clone()),
         }
     }
     result
 }
 
+/// Report names that appear more often in one block of the tickoff list than
+/// there are members with this name.
+/// Returns the amount of duplicated entries.
+pub fn check_duplicates(
+    list: &[TickOffItem],
+    members: &[member::Member],
+    reporter: &mut dyn Reporter,
+) -> usize {
+    let mut duplicates = 0;
+    let mut names: Vec<&str> = Vec::new();
+    for item in list.iter() {
+        if !names.contains(&item.name.as_str()) {
+            names.push(&item.name);
+        }
+    }
+    for name in names {
+        let allowed = members
+            .iter()
+            .filter(|m| check_member_name(m, name))
+            .count()
+            .max(1);
+        let types: Vec<&str> = list
+            .iter()
+            .filter(|t| t.name == name)
+            .flat_map(|t| t.shares.iter().map(|(s, _)| s))
+            .collect();
+        let mut reported: Vec<&str> = Vec::new();
+        for share_type in types {
+            if reported.contains(&share_type) {
+                continue;
+            }
+            reported.push(share_type);
+            let entries = list
+                .iter()
+                .filter(|t| t.name == name && t.shares.get(share_type) > 0);
+            for entry in entries.skip(allowed) {
+                reporter.error_at(
+                    format!(
+                        "Duplicated entry \"{name}\" in {share_type} block \
+                         of tickoff list"
+                    ),
+                    entry.source.clone(),
+                );
+                duplicates += 1;
+            }
+        }
+    }
+    duplicates
+}
+
 #[cfg(test)]
 mod tickoff_tests {
 
     use super::*;
+    use crate::member::{BIG, SMALL};
+    use crate::report::{MemoryReporter, QuietReporter, Severity};
     use crate::test_common::test_common::*;
 
     #[test]
     fn test_new() {
-        let _ = TickOffItem::try_new(
+        let t = TickOffItem::try_new(
             &Data::String("Test".to_string()),
-            Some(&Data::Int(5)),
-            Some(&Data::Int(6)),
+            SMALL,
+            &Data::Int(6),
+        )
+        .unwrap();
+        assert_eq!(t.shares, member::Shares::from([(SMALL, 6)]));
+        assert_eq!(t.shares.get(BIG), 0);
+        assert!(
+            TickOffItem::try_new(&Data::Empty, BIG, &Data::Int(6)).is_err()
         );
-        let _t = TickOffItem {
-            name: "Test".to_string(),
-            big: 2,
-            small: 3,
-        };
+    }
+
+    #[test]
+    fn test_tickoff_column() {
+        let types = member::share_types(None);
+        assert_eq!(tickoff_column(&types[0], &Location::Perouse), 0);
+        assert_eq!(tickoff_column(&types[1], &Location::Perouse), 4);
+        assert_eq!(tickoff_column(&types[1], &Location::Gerlingen), 5);
     }
 
     #[test]
     fn test_get_big_amount() {
         let toi = gen_toi_ok();
-        assert_eq!(get_amount_big(&toi), 5);
+        assert_eq!(get_amounts(&toi).get(BIG), 5);
     }
 
     #[test]
     fn test_get_small_amount() {
         let toi = gen_toi_ok();
-        assert_eq!(get_amount_small(&toi), 5);
+        assert_eq!(get_amounts(&toi).get(SMALL), 5);
+    }
+
+    #[test]
+    fn test_check_sums() {
+        let types = member::share_types(None);
+        let mut sheet = TickOffSheet {
+            items: gen_toi_ok().to_vec(),
+            sums: member::Shares::from([(BIG, 5), (SMALL, 5)]),
+        };
+        let mut reporter = MemoryReporter::default();
+        assert_eq!(check_sums(&sheet, &types, &mut reporter), 0);
+        sheet.sums.set(SMALL, 4);
+        assert_eq!(check_sums(&sheet, &types, &mut reporter), 1);
+        assert_eq!(reporter.count(Severity::Warning), 1);
     }
 
     #[test]
@@ -444,6 +635,8 @@ mod tickoff_tests {
         let r = check_for_members_in_tickoff_list(
             &vec![m.clone(), n.clone()],
             &vec![a.clone(), b.clone()],
+            &[],
+            &mut QuietReporter::default(),
         );
         assert!(r.is_ok(), "Base test");
         assert_eq!(r.unwrap(), None);
@@ -451,6 +644,8 @@ mod tickoff_tests {
         let r = check_for_members_in_tickoff_list(
             &vec![m.clone(), n.clone()],
             &vec![a_small.clone(), b.clone()],
+            &[],
+            &mut QuietReporter::default(),
         );
         assert!(r.is_ok(), "Error in small caps");
         assert_eq!(r.unwrap(), None);
@@ -459,6 +654,8 @@ mod tickoff_tests {
         let r = check_for_members_in_tickoff_list(
             &vec![m.clone(), n.clone()],
             &vec![a.clone()],
+            &[],
+            &mut QuietReporter::default(),
         );
         assert!(r.is_ok());
         assert_eq!(r.unwrap(), Some(1));
@@ -467,6 +664,8 @@ mod tickoff_tests {
         let r = check_for_members_in_tickoff_list(
             &vec![m.clone(), n.clone()],
             &vec![b.clone()],
+            &[],
+            &mut QuietReporter::default(),
         );
         assert!(r.is_ok());
         assert_eq!(r.unwrap(), Some(1));
@@ -475,33 +674,76 @@ mod tickoff_tests {
         let r = check_for_members_in_tickoff_list(
             &vec![m.clone(), n.clone()],
             &vec![],
+            &[],
+            &mut QuietReporter::default(),
         );
         assert_eq!(r.unwrap(), Some(2));
 
         // Invalid entry
+        let mut reporter = MemoryReporter::default();
         let r = check_for_members_in_tickoff_list(
             &vec![m.clone(), n.clone()],
             &vec![c.clone()],
+            &[],
+            &mut reporter,
         );
         assert!(r.is_ok());
-        assert_eq!(r.unwrap(), Some(4));
+        assert_eq!(r.unwrap(), Some(3));
+        assert_eq!(reporter.count(Severity::Warning), 1);
+        assert_eq!(reporter.count(Severity::Error), 2);
+    }
+
+    #[test]
+    fn test_check_for_members_in_tickoff_list_jokers() {
+        let [a, _a_small, b, _c] = gen_toi_fail();
+        let [m, n, _o] = gen_members();
 
-        // Additional tickoffs cannot be detected for now
-        // let r = check_for_members_in_tickoff_list(
-        //     &vec![m.clone(), n.clone()],
-        //     &vec![a.clone(), b.clone(), c.clone()],
-        // );
-        // assert!(r.is_err());
+        // Missing entry is explained by a joker
+        let r = check_for_members_in_tickoff_list(
+            &vec![m.clone(), n.clone()],
+            &vec![b.clone()],
+            &[gen_joker_a()],
+            &mut QuietReporter::default(),
+        );
+        assert_eq!(r.unwrap(), None);
+
+        // Additional tickoff for a member with joker
+        let r = check_for_members_in_tickoff_list(
+            &vec![m.clone(), n.clone()],
+            &vec![a.clone(), b.clone()],
+            &[gen_joker_a()],
+            &mut QuietReporter::default(),
+        );
+        assert_eq!(r.unwrap(), Some(1));
+
+        // Both have a joker, but both are listed
+        let r = check_for_members_in_tickoff_list(
+            &vec![m.clone(), n.clone()],
+            &vec![a.clone(), b.clone()],
+            &[gen_joker_a(), gen_joker_b()],
+            &mut QuietReporter::default(),
+        );
+        assert_eq!(r.unwrap(), Some(2));
+
+        // Jokers of other members do not explain a missing entry
+        let r = check_for_members_in_tickoff_list(
+            &vec![m.clone(), n.clone()],
+            &vec![a.clone()],
+            &[gen_joker_c()],
+            &mut QuietReporter::default(),
+        );
+        assert_eq!(r.unwrap(), Some(1));
     }
 
     #[test]
     fn test_check_tickoff_list_against_members() {
-        let [a, _a_small, b, _c] = gen_toi_fail();
+        let [a, _a_small, b, c] = gen_toi_fail();
         let [m, n, _c] = gen_members();
 
         let r = check_tickoff_list_against_members(
             &vec![m.clone(), n.clone()],
             &vec![a.clone(), b.clone()],
+            &mut QuietReporter::default(),
         );
         assert!(r.is_ok(), "Base test");
         assert_eq!(r.unwrap(), None);
@@ -509,6 +751,7 @@ mod tickoff_tests {
         let r = check_tickoff_list_against_members(
             &vec![m.clone()],
             &vec![a.clone(), b.clone()],
+            &mut QuietReporter::default(),
         );
         assert!(r.is_ok(), "One missing");
         assert_eq!(r.unwrap(), Some(1));
@@ -516,17 +759,27 @@ mod tickoff_tests {
         let r = check_tickoff_list_against_members(
             &vec![],
             &vec![a.clone(), b.clone()],
+            &mut QuietReporter::default(),
         );
         assert!(r.is_ok(), "Two missing");
         assert_eq!(r.unwrap(), Some(2));
 
+        // Malformed names are left to check_for_members_in_tickoff_list
+        let r = check_tickoff_list_against_members(
+            &vec![m.clone(), n.clone()],
+            &vec![a.clone(), b.clone(), c.clone()],
+            &mut QuietReporter::default(),
+        );
+        assert_eq!(r.unwrap(), None);
+
         // inactive
         let mut x = m.clone();
-        x.big = 77;
-        x.active = false;
+        x.shares.set(BIG, 77);
+        x.status = member::MemberStatus::Inactive;
         let r = check_tickoff_list_against_members(
             &vec![m.clone(), x, n.clone()],
             &vec![a.clone(), b.clone()],
+            &mut QuietReporter::default(),
         );
         assert!(r.is_ok(), "Base test");
         assert_eq!(r.unwrap(), None);
@@ -536,26 +789,106 @@ mod tickoff_tests {
     fn test_check_tickoff_list_wrong_size() {
         let [mut a, mut b, _c] = gen_toi_ok();
         let [m, n, _c] = gen_members();
-        a.big = 999;
+        a.shares.set(BIG, 999);
         let r = check_tickoff_list_against_members(
             &vec![m.clone(), n.clone()],
             &vec![a.clone(), b.clone()],
+            &mut QuietReporter::default(),
         );
         assert!(r.is_ok(), "Base test");
         assert_eq!(r.unwrap(), Some(1));
 
-        a.big = 99;
-        a.small = 32;
-        b.big = 42;
-        b.small = 11;
+        a.shares.set(BIG, 99);
+        a.shares.set(SMALL, 32);
+        b.shares.set(BIG, 42);
+        b.shares.set(SMALL, 11);
         let r = check_tickoff_list_against_members(
             &vec![m.clone(), n.clone()],
             &vec![a.clone(), b.clone()],
+            &mut QuietReporter::default(),
         );
         assert!(r.is_ok(), "Base test");
         assert_eq!(r.unwrap(), Some(4));
     }
 
+    #[test]
+    fn test_check_wrong_location() {
+        let [a, b, c] = gen_toi_ok();
+        let [m, n, mut o] = gen_members();
+        o.location = Location::Gerlingen;
+        let members = vec![m, n, o];
+
+        // Everybody is at the right place
+        let r = check_wrong_location(
+            &members,
+            &[
+                (Location::Perouse, vec![a.clone(), b.clone()]),
+                (Location::Gerlingen, vec![c.clone()]),
+            ],
+            &mut QuietReporter::default(),
+        );
+        assert!(r.is_empty());
+
+        // Testeress is listed in Perouse instead of Gerlingen
+        let r = check_wrong_location(
+            &members,
+            &[
+                (Location::Perouse, vec![a.clone(), b.clone(), c.clone()]),
+                (Location::Gerlingen, vec![]),
+            ],
+            &mut QuietReporter::default(),
+        );
+        assert_eq!(r.len(), 1);
+        assert_eq!(r[0].name, "Testeress, C.");
+        assert_eq!(r[0].member.location, Location::Gerlingen);
+        assert_eq!(r[0].listed_at, Location::Perouse);
+
+        // Listed at both places: the own location wins
+        let r = check_wrong_location(
+            &members,
+            &[
+                (Location::Perouse, vec![a, b, c.clone()]),
+                (Location::Gerlingen, vec![c]),
+            ],
+            &mut QuietReporter::default(),
+        );
+        assert!(r.is_empty());
+    }
+
+    #[test]
+    fn test_check_share_amounts() {
+        let toi = gen_toi_ok().to_vec();
+        let shares = member::count_shares(&gen_members());
+        let r =
+            check_share_amounts(&shares, &toi, &mut QuietReporter::default());
+        assert_eq!(r.unwrap(), None);
+
+        let r = check_share_amounts(
+            &member::Shares::from([(BIG, 5), (SMALL, 4)]),
+            &toi,
+            &mut QuietReporter::default(),
+        );
+        assert_eq!(r.unwrap(), Some(1));
+
+        let mut extra = shares.clone();
+        extra.set("bread", 1);
+        let mut reporter = MemoryReporter::default();
+        let r = check_share_amounts(&extra, &toi, &mut reporter);
+        assert_eq!(r.unwrap(), Some(1));
+        assert_eq!(
+            reporter.findings[0].finding.message,
+            "Tickoff amount for bread shares does not match: expected 1 \
+             listed 0"
+        );
+
+        let r = check_share_amounts(
+            &member::Shares::default(),
+            &toi,
+            &mut QuietReporter::default(),
+        );
+        assert_eq!(r.unwrap(), Some(2));
+    }
+
     #[test]
     fn test_check_initial() {
         assert!(check_name_with_initial("Smith, J."));
@@ -594,23 +927,140 @@ mod tickoff_tests {
         assert!(!check_name_equality("Smith", "Bob", "Smith, J."));
         assert!(!check_name_equality("Smith", "Bob", "Smith J."));
     }
+    #[test]
+    fn test_check_member_name() {
+        let [mut m, ..] = gen_members();
+        assert!(check_member_name(&m, "Testerin, A."));
+        assert!(!check_member_name(&m, "Nachbarin, N."));
+        m.co_holders.push(member::CoHolder {
+            surname: "Nachbarin".to_string(),
+            forename: "Nora".to_string(),
+            schedule: member::Schedule::Odd,
+        });
+        assert!(check_member_name(&m, "Testerin, A."));
+        assert!(check_member_name(&m, "Nachbarin, N."));
+    }
+
+    #[test]
+    fn test_check_schedules() {
+        let [mut m, n, _o] = gen_members();
+        m.co_holders.push(member::CoHolder {
+            surname: "Nachbarin".to_string(),
+            forename: "Nora".to_string(),
+            schedule: member::Schedule::Odd,
+        });
+        let [a, b, _c] = gen_toi_ok();
+        let mut nora = a.clone();
+        nora.name = "Nachbarin, N.".to_string();
+        let members = vec![m, n];
+        let check = |tickoff: &TickOffList, date: &chrono::NaiveDate| {
+            check_schedules(
+                &members,
+                tickoff,
+                &[],
+                date,
+                &mut QuietReporter::default(),
+            )
+        };
+
+        // Week 45 is odd, the co-holder collects
+        let odd = chrono::NaiveDate::from_ymd_opt(2025, 11, 7).unwrap();
+        assert_eq!(check(&vec![nora.clone(), b.clone()], &odd), 0);
+        assert_eq!(check(&vec![a.clone(), b.clone()], &odd), 1);
+
+        // Week 46 is even, the contract holder collects
+        let even = chrono::NaiveDate::from_ymd_opt(2025, 11, 14).unwrap();
+        assert_eq!(check(&vec![a.clone(), b.clone()], &even), 0);
+        assert_eq!(check(&vec![nora.clone(), b.clone()], &even), 1);
+
+        // A substitute is listed under the contract holder
+        let substitution = Substitution {
+            date: odd,
+            surname: "Nachbarin".to_string(),
+            forename: "Nora".to_string(),
+            substitute: "Helferin, H.".to_string(),
+            line: 2,
+        };
+        let mut reporter = MemoryReporter::default();
+        assert_eq!(
+            check_schedules(
+                &members,
+                &vec![a, b],
+                &[substitution],
+                &odd,
+                &mut reporter
+            ),
+            0
+        );
+    }
+
     #[test]
     fn test_deduplicate_a() {
-        let mut toi = gen_toi_ok();
-        let toid = deduplicate(&mut toi);
+        let toi = gen_toi_ok();
+        let toid = deduplicate(&toi);
         assert_eq!(toid.len(), toi.len());
     }
     #[test]
     fn test_deduplicate_b() {
-        let mut toi = gen_toi_duplication();
-        let toid = deduplicate(&mut toi);
+        let toi = gen_toi_duplication();
+        let toid = deduplicate(&toi);
         assert_eq!(toi.len(), 3);
         assert_eq!(toid.len(), 2);
         // Expect that elem 1 and 3 were combined
-        assert_eq!(toid.get(0).unwrap().big, 2);
-        assert_eq!(toid.get(0).unwrap().small, 3);
+        assert_eq!(toid[0].shares.get(BIG), 2);
+        assert_eq!(toid[0].shares.get(SMALL), 3);
         // Expect elem 2 is untouched
-        assert_eq!(toid.get(1).unwrap().big, 0);
-        assert_eq!(toid.get(1).unwrap().small, 2);
+        assert_eq!(toid[1].shares.get(BIG), 0);
+        assert_eq!(toid[1].shares.get(SMALL), 2);
+    }
+    #[test]
+    fn test_deduplicate_same_block() {
+        let [a, b, mut c] = gen_toi_duplication();
+        // Listed twice in the big block
+        c.shares.set(BIG, 1);
+        c.shares.set(SMALL, 0);
+        let list = [a.clone(), b.clone(), c];
+        let toid = deduplicate(&list);
+        assert_eq!(toid.len(), 3);
+        assert_eq!(get_amounts(&toid), get_amounts(&list));
+        let mut reporter = MemoryReporter::default();
+        assert_eq!(check_duplicates(&list, &gen_members(), &mut reporter), 1);
+        assert_eq!(reporter.count(Severity::Error), 1);
+
+        // Listed twice in the small block
+        let list = [b.clone(), a, b];
+        assert_eq!(deduplicate(&list).len(), 3);
+        assert_eq!(
+            check_duplicates(
+                &list,
+                &gen_members(),
+                &mut QuietReporter::default()
+            ),
+            1
+        );
+    }
+
+    #[test]
+    fn test_check_duplicates_same_initial() {
+        // Two members share the surname and the initial
+        let [a, ..] = gen_members();
+        let b = member::Member::new_from_values(
+            "EV-4",
+            4,
+            "Testerin",
+            "Anna",
+            1,
+            0,
+            Location::Perouse,
+            member::MemberStatus::Active,
+        );
+        let [t, ..] = gen_toi_duplication();
+        let list = [t.clone(), t.clone()];
+        let mut reporter = MemoryReporter::default();
+        assert_eq!(check_duplicates(&list, &[a.clone(), b], &mut reporter), 0);
+        assert_eq!(reporter.count(Severity::Error), 0);
+        assert_eq!(deduplicate(&list).len(), 2);
+        // Only one of them is a member
+        assert_eq!(check_duplicates(&list, &[a], &mut reporter), 1);
     }
 }
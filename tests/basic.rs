@@ -1,8 +1,13 @@
 use crate::somato::member;
 use anyhow::Result;
+use chrono::NaiveDate;
 use somato::Location;
 use somato::joker;
+use somato::redistribution;
+use somato::report::{MemoryReporter, QuietReporter, Severity};
 use somato::somato;
+use somato::source;
+use somato::substitution;
 use somato::tickoff;
 
 fn get_config_synth() -> somato::Config {
@@ -11,26 +16,129 @@ fn get_config_synth() -> somato::Config {
         jokers: "tests/test_data/jokers_synthetic.xlsx".to_string(),
         tickoff: "tests/test_data/tickoff_synthetic.xlsx".to_string(),
         date: "2025-12-19".to_string(),
+        ..Default::default()
+    }
+}
+
+fn get_config_synth_csv() -> somato::Config {
+    somato::Config {
+        members: "tests/test_data/members_synthetic.csv".to_string(),
+        jokers: "tests/test_data/jokers_synthetic.csv".to_string(),
+        tickoff: "tests/test_data/tickoff_synthetic_{sheet}.csv".to_string(),
+        date: "2025-12-19".to_string(),
+        ..Default::default()
     }
 }
 
 #[test]
 fn basic_load_synth() -> Result<(), anyhow::Error> {
     let config = get_config_synth();
-    somato::somato_runner(&config)
+    let analysis =
+        somato::somato_runner(&config, &mut QuietReporter::default())?;
+    assert_eq!(analysis.locations.len(), 7);
+    assert_eq!(analysis.locations[0].location, Location::Perouse);
+    Ok(())
+}
+
+#[test]
+fn basic_report_html() -> Result<(), anyhow::Error> {
+    let file = std::env::temp_dir().join("somato_basic_report.html");
+    let config = somato::Config {
+        report_html: Some(file.to_str().unwrap().to_string()),
+        ..get_config_synth()
+    };
+    somato::somato_runner(&config, &mut QuietReporter::default())?;
+    let html = std::fs::read_to_string(file)?;
+    assert!(html.contains("<h1>Somato report for 2025-12-19</h1>"));
+    assert!(html.contains("Duplicated surname: Duck"));
+    assert!(html.contains("<summary>Perouse ("));
+    Ok(())
+}
+
+#[test]
+fn basic_annotate_inputs() -> Result<(), anyhow::Error> {
+    let dir = std::env::temp_dir().join("somato_basic_annotate");
+    std::fs::create_dir_all(&dir)?;
+    let config = get_config_synth();
+    let analysis =
+        somato::somato_runner(&config, &mut QuietReporter::default())?;
+    let written = somato::write_annotated_inputs(
+        &config,
+        &analysis.findings,
+        dir.to_str().unwrap(),
+        &mut QuietReporter::default(),
+    )?;
+    assert_eq!(written.len(), 3);
+    for (file, _) in written.iter() {
+        assert!(std::path::Path::new(file).exists());
+    }
+    // Duplicated members and tickoff entries, no problems with jokers
+    assert!(written[0].1 > 0);
+    assert_eq!(written[1].1, 0);
+    assert!(written[2].1 > 0);
+
+    // The same file name in another directory gets a numbered copy
+    let other = std::env::temp_dir().join("somato_basic_annotate_other");
+    std::fs::create_dir_all(&other)?;
+    let jokers = other.join("members_synthetic.xlsx");
+    std::fs::copy(&config.jokers, &jokers)?;
+    let config = somato::Config {
+        jokers: jokers.to_str().unwrap().to_string(),
+        ..config
+    };
+    let written = somato::write_annotated_inputs(
+        &config,
+        &analysis.findings,
+        dir.to_str().unwrap(),
+        &mut QuietReporter::default(),
+    )?;
+    assert_ne!(written[0].0, written[1].0);
+    assert!(written[1].0.ends_with("members_synthetic_annotated_2.xlsx"));
+
+    // CSV inputs are skipped, the run does not fail
+    let config = somato::Config {
+        annotate_dir: Some(dir.to_str().unwrap().to_string()),
+        ..get_config_synth_csv()
+    };
+    let mut reporter = MemoryReporter::default();
+    let analysis = somato::somato_runner(&config, &mut reporter)?;
+    assert!(
+        reporter
+            .findings
+            .iter()
+            .any(|f| f.finding.message.contains("Cannot annotate CSV file"))
+    );
+    let mut reporter = MemoryReporter::default();
+    let written = somato::write_annotated_inputs(
+        &config,
+        &analysis.findings,
+        dir.to_str().unwrap(),
+        &mut reporter,
+    )?;
+    assert!(written.is_empty());
+    assert_eq!(reporter.count(Severity::Warning), 3);
+    Ok(())
 }
 
 #[test]
 fn basic_read_members() -> Result<(), anyhow::Error> {
     let members_count = 93;
     let config = get_config_synth();
-    let members = member::read_members(&config.members)?;
+    let members = member::read_members(
+        &config.members,
+        None,
+        &member::status_map(None),
+        &member::share_types(None),
+    )?;
     assert_eq!(members.len(), members_count);
     // make sure the first is there
     let m = members
         .iter()
-        .filter(|m| m.forename == "Donald" && m.surname == "Duck");
-    assert_eq!(m.count(), 1);
+        .filter(|m| m.forename == "Donald" && m.surname == "Duck")
+        .collect::<Vec<_>>();
+    assert_eq!(m.len(), 1);
+    assert_eq!(m[0].start, NaiveDate::from_ymd_opt(2020, 1, 1));
+    assert_eq!(m[0].end, NaiveDate::from_ymd_opt(2024, 1, 1));
     // make sure the last is there
     let m = members
         .iter()
@@ -42,8 +150,15 @@ fn basic_read_members() -> Result<(), anyhow::Error> {
 #[test]
 fn basic_read_members_warnings() {
     let config = get_config_synth();
-    let members = member::read_members(&config.members).unwrap();
-    let warnings = member::check_member_list(&members);
+    let members = member::read_members(
+        &config.members,
+        None,
+        &member::status_map(None),
+        &member::share_types(None),
+    )
+    .unwrap();
+    let warnings =
+        member::check_member_list(&members, &mut QuietReporter::default());
     assert_eq!(warnings, 17);
 }
 
@@ -51,7 +166,8 @@ fn basic_read_members_warnings() {
 fn basic_read_jokers() -> Result<(), anyhow::Error> {
     let jokers_count = 15;
     let config = get_config_synth();
-    let jokers = joker::read_jokers(&config.jokers)?;
+    let jokers =
+        joker::read_jokers(&config.jokers, None, &member::share_types(None))?;
     assert_eq!(jokers.len(), jokers_count);
     Ok(())
 }
@@ -65,17 +181,53 @@ fn basic_read_tickoff_count() -> Result<(), anyhow::Error> {
     let to_count_wds = 11;
     let to_count_neu = 11;
     let config = get_config_synth();
-    let to = tickoff::tick_off_list(&config.tickoff, &Location::Perouse)?;
+    let to = tickoff::tick_off_sheet(
+        &config.tickoff,
+        &Location::Perouse,
+        None,
+        &member::share_types(None),
+    )?
+    .items;
     assert_eq!(to.len(), to_count_per);
-    let to = tickoff::tick_off_list(&config.tickoff, &Location::Renningen)?;
+    let to = tickoff::tick_off_sheet(
+        &config.tickoff,
+        &Location::Renningen,
+        None,
+        &member::share_types(None),
+    )?
+    .items;
     assert_eq!(to.len(), to_count_ren);
-    let to = tickoff::tick_off_list(&config.tickoff, &Location::Gerlingen)?;
+    let to = tickoff::tick_off_sheet(
+        &config.tickoff,
+        &Location::Gerlingen,
+        None,
+        &member::share_types(None),
+    )?
+    .items;
     assert_eq!(to.len(), to_count_ger);
-    let to = tickoff::tick_off_list(&config.tickoff, &Location::Leonberg)?;
+    let to = tickoff::tick_off_sheet(
+        &config.tickoff,
+        &Location::Leonberg,
+        None,
+        &member::share_types(None),
+    )?
+    .items;
     assert_eq!(to.len(), to_count_leo);
-    let to = tickoff::tick_off_list(&config.tickoff, &Location::WeilDerStadt)?;
+    let to = tickoff::tick_off_sheet(
+        &config.tickoff,
+        &Location::WeilDerStadt,
+        None,
+        &member::share_types(None),
+    )?
+    .items;
     assert_eq!(to.len(), to_count_wds);
-    let to = tickoff::tick_off_list(&config.tickoff, &Location::Neuhausen)?;
+    let to = tickoff::tick_off_sheet(
+        &config.tickoff,
+        &Location::Neuhausen,
+        None,
+        &member::share_types(None),
+    )?
+    .items;
     assert_eq!(to.len(), to_count_neu);
     Ok(())
 }
@@ -92,7 +244,13 @@ fn basic_read_tickoff_count_dense() -> Result<(), anyhow::Error> {
     ];
     let config = get_config_synth();
     for toi in to_count.iter() {
-        let to = tickoff::tick_off_list(&config.tickoff, &toi.0)?;
+        let to = tickoff::tick_off_sheet(
+            &config.tickoff,
+            &toi.0,
+            None,
+            &member::share_types(None),
+        )?
+        .items;
         assert_eq!(to.len(), toi.1);
     }
     Ok(())
@@ -111,13 +269,346 @@ fn basic_read_tickoff_counts() -> Result<(), anyhow::Error> {
     let config = get_config_synth();
     for toi in to_count.iter() {
         println!("{:?}", toi.0);
-        let to = tickoff::tick_off_list(&config.tickoff, &toi.0)?;
-        assert_eq!(tickoff::get_amount_big(&to), toi.1, "big fail");
-        assert_eq!(tickoff::get_amount_small(&to), toi.2, "small fail");
+        let to = tickoff::tick_off_sheet(
+            &config.tickoff,
+            &toi.0,
+            None,
+            &member::share_types(None),
+        )?
+        .items;
+        assert_eq!(
+            tickoff::get_amounts(&to).get(member::BIG),
+            toi.1,
+            "big fail"
+        );
+        assert_eq!(
+            tickoff::get_amounts(&to).get(member::SMALL),
+            toi.2,
+            "small fail"
+        );
     }
     Ok(())
 }
 
+#[test]
+fn basic_load_synth_csv() -> Result<(), anyhow::Error> {
+    let config = get_config_synth_csv();
+    somato::somato_runner(&config, &mut QuietReporter::default())?;
+    Ok(())
+}
+
+#[test]
+fn basic_read_csv_same_as_xlsx() -> Result<(), anyhow::Error> {
+    let xlsx = get_config_synth();
+    let csv = get_config_synth_csv();
+    let members_xlsx = member::read_members(
+        &xlsx.members,
+        None,
+        &member::status_map(None),
+        &member::share_types(None),
+    )?;
+    let members_csv = member::read_members(
+        &csv.members,
+        None,
+        &member::status_map(None),
+        &member::share_types(None),
+    )?;
+    assert_eq!(members_xlsx.len(), members_csv.len());
+    for (x, c) in members_xlsx.iter().zip(members_csv.iter()) {
+        assert_eq!(x.to_string(), c.to_string());
+    }
+    let jokers_xlsx =
+        joker::read_jokers(&xlsx.jokers, None, &member::share_types(None))?;
+    let jokers_csv =
+        joker::read_jokers(&csv.jokers, None, &member::share_types(None))?;
+    assert_eq!(jokers_xlsx.len(), jokers_csv.len());
+    for (x, c) in jokers_xlsx.iter().zip(jokers_csv.iter()) {
+        assert_eq!(x.to_string(), c.to_string());
+    }
+    for location in [Location::Perouse, Location::Leonberg] {
+        let to_xlsx = tickoff::tick_off_sheet(
+            &xlsx.tickoff,
+            &location,
+            None,
+            &member::share_types(None),
+        )?
+        .items;
+        let to_csv = tickoff::tick_off_sheet(
+            &csv.tickoff,
+            &location,
+            None,
+            &member::share_types(None),
+        )?
+        .items;
+        assert_eq!(to_xlsx, to_csv);
+    }
+    Ok(())
+}
+
+#[test]
+fn basic_diff_member_files() -> Result<(), anyhow::Error> {
+    let xlsx = get_config_synth();
+    let csv = get_config_synth_csv();
+    let changes = member::diff_member_files(
+        &xlsx.members,
+        &csv.members,
+        None,
+        &member::status_map(None),
+        &member::share_types(None),
+        &mut QuietReporter::default(),
+    )?;
+    assert!(changes.is_empty());
+    Ok(())
+}
+
+#[test]
+fn basic_diff_member_files_format() -> Result<(), anyhow::Error> {
+    // A file without a known extension is read in the configured format
+    let csv = get_config_synth_csv();
+    let file = std::env::temp_dir().join("somato_basic_members.txt");
+    std::fs::copy(&csv.members, &file)?;
+    let file = file.to_str().unwrap();
+    let changes = member::diff_member_files(
+        &csv.members,
+        file,
+        Some(somato::input::InputFormat::Csv),
+        &member::status_map(None),
+        &member::share_types(None),
+        &mut QuietReporter::default(),
+    )?;
+    assert!(changes.is_empty());
+    assert!(
+        member::diff_member_files(
+            &csv.members,
+            file,
+            None,
+            &member::status_map(None),
+            &member::share_types(None),
+            &mut QuietReporter::default(),
+        )
+        .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn basic_diff_member_files_status() -> Result<(), anyhow::Error> {
+    // A status text that is only known from the configuration
+    let csv = get_config_synth_csv();
+    let text = std::fs::read_to_string(&csv.members)?.replacen(
+        ",inaktiv,",
+        ",Probeabo,",
+        1,
+    );
+    let file = std::env::temp_dir().join("somato_basic_status.csv");
+    std::fs::write(&file, text)?;
+    let file = file.to_str().unwrap();
+    let statuses = member::status_map(Some(&member::StatusMap::from([(
+        "Probeabo".to_string(),
+        member::MemberStatus::Trial,
+    )])));
+    let changes = member::diff_member_files(
+        &csv.members,
+        file,
+        None,
+        &statuses,
+        &member::share_types(None),
+        &mut QuietReporter::default(),
+    )?;
+    assert_eq!(changes.len(), 1);
+    assert!(
+        member::diff_member_files(
+            &csv.members,
+            file,
+            None,
+            &member::status_map(None),
+            &member::share_types(None),
+            &mut QuietReporter::default(),
+        )
+        .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn basic_diff_member_files_share_types() -> Result<(), anyhow::Error> {
+    // Only the small shares change, which are not a configured share type
+    let csv = get_config_synth_csv();
+    let text = std::fs::read_to_string(&csv.members)?.replacen(
+        "Donald,-,1,0,",
+        "Donald,-,1,1,",
+        1,
+    );
+    let file = std::env::temp_dir().join("somato_basic_share_types.csv");
+    std::fs::write(&file, text)?;
+    let file = file.to_str().unwrap();
+    let diff = |types: &[member::ShareType]| {
+        member::diff_member_files(
+            &csv.members,
+            file,
+            None,
+            &member::status_map(None),
+            types,
+            &mut QuietReporter::default(),
+        )
+    };
+    assert_eq!(diff(&member::share_types(None))?.len(), 1);
+    assert!(diff(&member::share_types(None)[..1])?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn basic_read_co_holders() -> Result<(), anyhow::Error> {
+    let config = get_config_synth_csv();
+    let mut members = member::read_members(
+        &config.members,
+        None,
+        &member::status_map(None),
+        &member::share_types(None),
+    )?;
+    member::read_co_holders(
+        "tests/test_data/co_holders_synthetic.csv",
+        None,
+        &mut members,
+    )?;
+    let m = members.iter().find(|m| m.contract_no == "EV-0012").unwrap();
+    assert!(m.is_holder("Maus", "Minnie"));
+    assert_eq!(m.co_holders[0].schedule, member::Schedule::Even);
+    // Week 51 belongs to the contract holder
+    let date = NaiveDate::from_ymd_opt(2025, 12, 19).unwrap();
+    assert_eq!(m.collector_at(&date), ("Duck", "Daisy"));
+    let config = somato::Config {
+        co_holders: Some(
+            "tests/test_data/co_holders_synthetic.csv".to_string(),
+        ),
+        ..config
+    };
+    somato::somato_runner(&config, &mut QuietReporter::default())?;
+    Ok(())
+}
+
+#[test]
+fn basic_read_substitutions() -> Result<(), anyhow::Error> {
+    let substitutions = substitution::read_substitutions(
+        "tests/test_data/substitutions_synthetic.csv",
+        None,
+    )?;
+    assert_eq!(substitutions.len(), 2);
+    assert_eq!(substitutions[0].surname, "Duck");
+    assert_eq!(substitutions[0].substitute, "Maus, M.");
+    let config = somato::Config {
+        substitutions: Some(
+            "tests/test_data/substitutions_synthetic.csv".to_string(),
+        ),
+        ..get_config_synth_csv()
+    };
+    let analysis =
+        somato::somato_runner(&config, &mut QuietReporter::default())?;
+    let without = somato::somato_runner(
+        &get_config_synth_csv(),
+        &mut QuietReporter::default(),
+    )?;
+    assert_eq!(analysis.warnings, without.warnings);
+    Ok(())
+}
+
+#[test]
+fn basic_read_redistributions() -> Result<(), anyhow::Error> {
+    let file = "tests/test_data/redistributions_synthetic.csv";
+    let redistributions = redistribution::read_redistributions(
+        file,
+        None,
+        &member::share_types(None),
+    )?;
+    assert_eq!(redistributions.len(), 2);
+    assert_eq!(redistributions[0].from, Location::Perouse);
+    assert_eq!(redistributions[0].to, Location::Neuhausen);
+    assert_eq!(redistributions[1].shares.get(member::SMALL), 1);
+    let config = somato::Config {
+        redistributions: Some(file.to_string()),
+        ..get_config_synth_csv()
+    };
+    let analysis =
+        somato::somato_runner(&config, &mut QuietReporter::default())?;
+    let without = somato::somato_runner(
+        &get_config_synth_csv(),
+        &mut QuietReporter::default(),
+    )?;
+    // Nothing jokered at Perouse, the recipient is missing at Neuhausen and
+    // the tickoff amounts there lack the redistributed share
+    assert_eq!(analysis.warnings, without.warnings + 3);
+    Ok(())
+}
+
+#[test]
+fn basic_rebalance_synth() -> Result<(), anyhow::Error> {
+    let distance =
+        |from, to, distance| somato::rebalance::Distance { from, to, distance };
+    let config = somato::Config {
+        capacity: Some(vec![somato::capacity::Capacity {
+            location: Location::Leonberg,
+            collectors: Some(16),
+            shares: None,
+        }]),
+        distances: Some(vec![
+            distance(Location::Perouse, Location::Leonberg, 4),
+            distance(Location::Perouse, Location::Renningen, 9),
+            distance(Location::Gerlingen, Location::Leonberg, 6),
+        ]),
+        ..get_config_synth()
+    };
+    let source = somato::source::SpreadsheetSource::from_config(&config);
+    let proposals = somato::rebalance_with_source(
+        &source,
+        &config,
+        &mut QuietReporter::default(),
+    )?;
+    let members = source::MemberSource::members(&source)?;
+    let date = NaiveDate::from_ymd_opt(2025, 12, 19).unwrap();
+    let active = member::filter_active_members(members, &date);
+    let count = |l: &Location| {
+        active.iter().filter(|m| m.location == *l).count() as i64
+            - proposals.iter().filter(|p| p.from == *l).count() as i64
+            + proposals.iter().filter(|p| p.to == *l).count() as i64
+    };
+    assert!(count(&Location::Leonberg) <= 16);
+    for p in proposals.iter() {
+        assert!(p.from != Location::Neuhausen && p.to != Location::Neuhausen);
+    }
+    Ok(())
+}
+
+#[test]
+fn basic_statistics_synth() -> Result<(), anyhow::Error> {
+    let config = get_config_synth();
+    let source = somato::source::SpreadsheetSource::from_config(&config);
+    let statistics = somato::statistics_with_source(
+        &source,
+        &config,
+        &mut QuietReporter::default(),
+    )?;
+    // Jokers without a parsed location belong to no depot
+    let jokers = source::JokerSource::jokers(&source)?;
+    let located = jokers
+        .iter()
+        .filter(|j| j.location != Location::NotParsed)
+        .count();
+    let total: usize = statistics.weeks.iter().map(|w| w.total()).sum();
+    assert_eq!(total, located);
+    assert_eq!(
+        statistics.peak_weeks[0],
+        (NaiveDate::from_ymd_opt(2025, 11, 7).unwrap(), 10)
+    );
+    assert!(!statistics.weeks.is_empty());
+    assert!(statistics.peak_weeks.len() <= somato::statistics::PEAK_WEEKS);
+    assert!(
+        statistics
+            .pickup_rate
+            .is_some_and(|r| (0.0..=1.0).contains(&r))
+    );
+    Ok(())
+}
+
 #[ignore]
 #[test]
 fn basic_load_real() {
@@ -135,8 +626,9 @@ fn basic_load_real() {
             2024-10-28_Abhaklisten.xlsx"
             .to_string(),
         date: "2025-12-19".to_string(),
+        ..Default::default()
     };
-    let result = somato::somato_runner(&config);
+    let result = somato::somato_runner(&config, &mut QuietReporter::default());
     println!("{:?}", result);
     assert!(result.is_ok());
 }